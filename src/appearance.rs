@@ -0,0 +1,90 @@
+use eframe::egui;
+use eframe::epaint::{Color32, FontFamily, FontId};
+use serde::{Deserialize, Serialize};
+
+pub const MIN_ZOOM: f32 = 0.5;
+pub const MAX_ZOOM: f32 = 4.0;
+
+pub const MIN_FONT_SIZE: f32 = 8.0;
+pub const MAX_FONT_SIZE: f32 = 32.0;
+
+// Headings are this much larger than the article text
+const HEADING_SCALE: f32 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Theme {
+    Light,
+    Dark,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ArticleFont {
+    Monospace,
+    Proportional,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Appearance {
+    // Pixels per point
+    pub zoom: f32,
+    pub theme: Theme,
+    pub article_font: ArticleFont,
+    pub font_size: f32,
+    pub highlight_text: Color32,
+    pub highlight_background: Color32,
+}
+
+impl Default for Appearance {
+    fn default() -> Self {
+        Self {
+            zoom: 2.0,
+            theme: Theme::Dark,
+            article_font: ArticleFont::Monospace,
+            font_size: 12.0,
+            highlight_text: Color32::BLACK,
+            highlight_background: Color32::LIGHT_BLUE,
+        }
+    }
+}
+
+// (name, text, background). The color-blind friendly ones are taken from the
+// Okabe-Ito palette.
+pub fn highlight_presets() -> Vec<(&'static str, Color32, Color32)> {
+    vec![
+        ("Light blue", Color32::BLACK, Color32::LIGHT_BLUE),
+        ("Yellow", Color32::BLACK, Color32::from_rgb(255, 230, 0)),
+        ("Orange (color-blind friendly)", Color32::BLACK, Color32::from_rgb(230, 159, 0)),
+        ("Sky blue (color-blind friendly)", Color32::BLACK, Color32::from_rgb(86, 180, 233)),
+        ("Blue (color-blind friendly)", Color32::WHITE, Color32::from_rgb(0, 114, 178)),
+        ("High contrast", Color32::WHITE, Color32::BLACK),
+    ]
+}
+
+impl Appearance {
+    fn font_family(&self) -> FontFamily {
+        match self.article_font {
+            ArticleFont::Monospace => FontFamily::Monospace,
+            ArticleFont::Proportional => FontFamily::Proportional,
+        }
+    }
+
+    pub fn article_font_id(&self) -> FontId {
+        FontId::new(self.font_size, self.font_family())
+    }
+
+    pub fn heading_font_id(&self) -> FontId {
+        FontId::new(self.font_size * HEADING_SCALE, self.font_family())
+    }
+
+    // Only needs to be called when something changed, egui keeps the
+    // settings between frames.
+    pub fn apply(&self, ctx: &egui::Context) {
+        ctx.set_pixels_per_point(self.zoom.clamp(MIN_ZOOM, MAX_ZOOM));
+
+        ctx.set_visuals(match self.theme {
+            Theme::Light => egui::Visuals::light(),
+            Theme::Dark => egui::Visuals::dark(),
+        });
+    }
+}
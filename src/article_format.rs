@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::article_parser::WikiArticle;
+
+// Bump whenever Token, Section or WikiArticle change in an incompatible way.
+pub const SCHEMA_VERSION: u32 = 4;
+
+const BINARY_MAGIC: &[u8; 4] = b"RSDA";
+
+#[derive(Serialize)]
+struct VersionedArticleRef<'a> {
+    version: u32,
+    article: &'a WikiArticle,
+}
+
+#[derive(Deserialize)]
+struct VersionedArticle {
+    // Checked before deserializing, but bincode needs it to find the article.
+    #[allow(dead_code)]
+    version: u32,
+    article: WikiArticle,
+}
+
+fn check_version(version: u32) -> Result<()> {
+    if version != SCHEMA_VERSION {
+        return Err(anyhow!("Article has schema version {}, but only version {} is supported", version, SCHEMA_VERSION));
+    }
+    Ok(())
+}
+
+pub fn to_json(article: &WikiArticle) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&to_value(article)?)?)
+}
+
+pub fn from_json(json: &str) -> Result<WikiArticle> {
+    from_value(serde_json::from_str(json)?)
+}
+
+// The JSON form as a value, for embedding articles in other files.
+pub fn to_value(article: &WikiArticle) -> Result<Value> {
+    let versioned = VersionedArticleRef { version: SCHEMA_VERSION, article: article };
+    Ok(serde_json::to_value(&versioned)?)
+}
+
+pub fn from_value(value: Value) -> Result<WikiArticle> {
+    // The version is checked first so that an outdated file gives a clear
+    // error instead of a confusing deserialization failure.
+    let version = value
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .ok_or(anyhow!("Article does not contain a schema version"))?;
+    let version = u32::try_from(version).map_err(|_| anyhow!("Article has an invalid schema version {}", version))?;
+    check_version(version)?;
+
+    let versioned: VersionedArticle = serde_json::from_value(value)?;
+    Ok(versioned.article)
+}
+
+// The binary form is the magic bytes followed by the bincode encoding of the
+// versioned article, which starts with the version as a little endian u32.
+pub fn to_binary(article: &WikiArticle) -> Result<Vec<u8>> {
+    let versioned = VersionedArticleRef { version: SCHEMA_VERSION, article: article };
+
+    let mut result = BINARY_MAGIC.to_vec();
+    result.extend(bincode::serialize(&versioned)?);
+    Ok(result)
+}
+
+pub fn from_binary(bytes: &[u8]) -> Result<WikiArticle> {
+    let Some(content) = bytes.strip_prefix(BINARY_MAGIC) else {
+        return Err(anyhow!("Not a binary rsdctl article"));
+    };
+
+    let version_bytes: [u8; 4] = content
+        .get(0..4)
+        .and_then(|v| v.try_into().ok())
+        .ok_or(anyhow!("Article does not contain a schema version"))?;
+    check_version(u32::from_le_bytes(version_bytes))?;
+
+    let versioned: VersionedArticle = bincode::deserialize(content)?;
+    Ok(versioned.article)
+}
+
+pub fn is_article_file(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+
+    matches!(extension.as_deref(), Some("json") | Some("rsda"))
+}
+
+pub fn load(path: &Path) -> Result<WikiArticle> {
+    let bytes = std::fs::read(path)?;
+
+    if bytes.starts_with(BINARY_MAGIC) {
+        from_binary(&bytes)
+    } else {
+        from_json(std::str::from_utf8(&bytes)?)
+    }
+}
+
+// Writes the binary form for .rsda files and JSON otherwise.
+pub fn save(path: &Path, article: &WikiArticle) -> Result<()> {
+    let is_binary = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.eq_ignore_ascii_case("rsda"))
+        .unwrap_or(false);
+
+    if is_binary {
+        std::fs::write(path, to_binary(article)?)?;
+    } else {
+        std::fs::write(path, to_json(article)?)?;
+    }
+
+    Ok(())
+}
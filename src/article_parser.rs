@@ -1,12 +1,27 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
 use parse_wiki_text::{Configuration, Node};
+use serde::{Deserialize, Serialize};
+
+use crate::tokenizer::{chop_into_tokens, TokenizerOptions};
+
+// Inline formatting a token had in the source. Links keep their target.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Style {
+    pub bold: bool,
+    pub italic: bool,
+    pub link: Option<String>,
+}
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Token {
-    Word(String),
-    NonWord(String),
+    Word(String, Style),
+    NonWord(String, Style),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Section {
     Heading(usize, Vec<Token>),
     Paragraph(Vec<Token>),
@@ -14,59 +29,146 @@ pub enum Section {
     OrderedList(Vec<Vec<Section>>),
 }
 
-#[derive(Debug)]
+// A heading together with everything up to the next heading of the same or a
+// higher level.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub level: usize,
+    pub heading: Vec<Token>,
+    pub content: Vec<Section>,
+    pub subchapters: Vec<Chapter>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct WikiArticle {
     pub title: Vec<Token>,
-    pub content: Vec<Section>,
+    // Everything before the first heading
+    pub lead: Vec<Section>,
+    pub chapters: Vec<Chapter>,
+    // How the article was split into words, which guesses have to follow
+    #[serde(default)]
+    pub tokenizer_options: TokenizerOptions,
 }
 
-fn chop_into_tokens(input: &str) -> Vec<Token> {
+fn attach_chapter(chapter: Chapter, stack: &mut [Chapter], chapters: &mut Vec<Chapter>) {
+    match stack.last_mut() {
+        Some(parent) => parent.subchapters.push(chapter),
+        None => chapters.push(chapter),
+    }
+}
 
-    let mut result: Vec<Token> = Vec::new();
-    let mut current: Vec<char> = Vec::new();
+// Groups a flat list of sections under their headings.
+fn build_outline(sections: Vec<Section>) -> (Vec<Section>, Vec<Chapter>) {
+    let mut lead: Vec<Section> = Vec::new();
+    let mut chapters: Vec<Chapter> = Vec::new();
 
-    let chars: Vec<char> = input.chars().collect();
-    let mut i = 0;
+    // Chapters that may still get content, with increasing levels
+    let mut stack: Vec<Chapter> = Vec::new();
 
-    // Check for a possible NonWord at the beginning.
-    while i < chars.len() && !chars[i].is_alphanumeric() {
-        current.push(chars[i]);
-        i += 1;
+    for section in sections {
+        match section {
+            Section::Heading(level, tokens) => {
+                while stack.last().map(|open| open.level >= level).unwrap_or(false) {
+                    let done = stack.pop().unwrap();
+                    attach_chapter(done, &mut stack, &mut chapters);
+                }
+
+                stack.push(Chapter {
+                    level: level,
+                    heading: tokens,
+                    content: Vec::new(),
+                    subchapters: Vec::new(),
+                });
+            }
+
+            other => {
+                match stack.last_mut() {
+                    Some(open) => open.content.push(other),
+                    None => lead.push(other),
+                }
+            }
+        }
     }
 
-    if current.len() > 0 {
-        result.push(Token::NonWord(current.into_iter().collect()));
+    while let Some(done) = stack.pop() {
+        attach_chapter(done, &mut stack, &mut chapters);
     }
 
-    if i == chars.len() { return result; }
+    (lead, chapters)
+}
 
-    loop {
-        // Now, we alternative between Words and NonWords until the end.
+fn count_words_in_tokens(tokens: &Vec<Token>, counts: &mut BTreeMap<String, usize>) {
+    for token in tokens {
+        if let Token::Word(w, _) = token {
+            *counts.entry(w.to_lowercase()).or_insert(0) += 1;
+        }
+    }
+}
 
-        let mut current: Vec<char> = Vec::new();
+fn count_words_in_sections(sections: &Vec<Section>, counts: &mut BTreeMap<String, usize>) {
+    for section in sections {
+        match section {
+            Section::Heading(_, tokens) | Section::Paragraph(tokens) => {
+                count_words_in_tokens(tokens, counts);
+            }
 
-        while i < chars.len() && chars[i].is_alphanumeric() {
-            current.push(chars[i]);
-            i += 1;
+            Section::UnorderedList(items) | Section::OrderedList(items) => {
+                for item in items {
+                    count_words_in_sections(item, counts);
+                }
+            }
         }
-        assert!(current.len() > 0);
-        result.push(Token::Word(current.into_iter().collect()));
+    }
+}
 
-        if i == chars.len() { break; }
+fn count_words_in_chapters(chapters: &Vec<Chapter>, counts: &mut BTreeMap<String, usize>) {
+    for chapter in chapters {
+        count_words_in_tokens(&chapter.heading, counts);
+        count_words_in_sections(&chapter.content, counts);
+        count_words_in_chapters(&chapter.subchapters, counts);
+    }
+}
 
-        let mut current: Vec<char> = Vec::new();
+impl Chapter {
+    // This chapter and all of its subchapters
+    pub fn count(&self) -> usize {
+        1 + self.subchapters.iter().map(|chapter| chapter.count()).sum::<usize>()
+    }
+}
 
-        while i < chars.len() && !chars[i].is_alphanumeric() {
-            current.push(chars[i]);
-            i += 1;
+impl WikiArticle {
+    pub fn new(title: Vec<Token>, sections: Vec<Section>, options: &TokenizerOptions) -> Self {
+        let (lead, chapters) = build_outline(sections);
+
+        Self {
+            title: title,
+            lead: lead,
+            chapters: chapters,
+            tokenizer_options: options.clone(),
         }
-        assert!(current.len() > 0);
-        result.push(Token::NonWord(current.into_iter().collect()));
+    }
 
-        if i == chars.len() { break; }
+    pub fn chapter_count(&self) -> usize {
+        self.chapters.iter().map(|chapter| chapter.count()).sum()
     }
 
-    result
+    // Occurrences of every (lowercased) word in the body, without the title.
+    pub fn content_word_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        count_words_in_sections(&self.lead, &mut counts);
+        count_words_in_chapters(&self.chapters, &mut counts);
+        counts
+    }
+
+    pub fn title_words(&self) -> Vec<String> {
+        self.title
+            .iter()
+            .filter_map(|token| match token {
+                Token::Word(w, _) => Some(w.to_lowercase()),
+                Token::NonWord(..) => None,
+            })
+            .collect()
+    }
 }
 
 fn get_parameter_by_name(parameters: &Vec<parse_wiki_text::Parameter>, name: &str) -> Option<String> {
@@ -216,10 +318,65 @@ fn get_inline_text(nodes: &Vec<Node>) -> String {
     result
 }
 
-fn get_sections(nodes: &Vec<Node>) -> Vec<Section> {
+// Paragraph text together with the style of each stretch of it.
+#[derive(Default)]
+struct StyledText {
+    text: String,
+    // Byte offset where each style starts, in increasing order
+    styles: Vec<(usize, Style)>,
+}
+
+impl StyledText {
+    fn push_str(&mut self, text: &str, style: &Style) {
+        if text.is_empty() {
+            return;
+        }
+
+        if self.styles.last().map(|(_, last)| last != style).unwrap_or(true) {
+            self.styles.push((self.text.len(), style.clone()));
+        }
+
+        self.text.push_str(text);
+    }
+
+    fn style_at(&self, offset: usize) -> Style {
+        self.styles
+            .iter()
+            .rev()
+            .find(|(start, _)| *start <= offset)
+            .map(|(_, style)| style.clone())
+            .unwrap_or_default()
+    }
+
+    fn is_blank(&self) -> bool {
+        self.text.trim().is_empty()
+    }
+
+    // A token gets the style of its first character, so that a word that is
+    // only partly bold is still one word.
+    fn chop_into_tokens(&self, options: &TokenizerOptions) -> Vec<Token> {
+        let trimmed = self.text.trim();
+        let mut offset = self.text.len() - self.text.trim_start().len();
+
+        let mut tokens = chop_into_tokens(trimmed, options);
+
+        for token in tokens.iter_mut() {
+            let (Token::Word(text, style) | Token::NonWord(text, style)) = token;
+            *style = self.style_at(offset);
+            offset += text.len();
+        }
+
+        tokens
+    }
+}
+
+fn get_sections(nodes: &Vec<Node>, options: &TokenizerOptions) -> Vec<Section> {
 
     let mut result: Vec<Section> = Vec::new();
-    let mut current_para = String::new();
+    let mut current_para = StyledText::default();
+
+    // Bold and italic markers toggle the style until the end of the paragraph
+    let mut style = Style::default();
 
     for node in nodes {
         // These nodes end the previous paragraph
@@ -229,18 +386,28 @@ fn get_sections(nodes: &Vec<Node>) -> Vec<Section> {
             Node::OrderedList { .. } |
             Node::ParagraphBreak { .. } |
             Node::UnorderedList { .. } => {
-                if !current_para.trim().is_empty() {
-                    result.push(Section::Paragraph(chop_into_tokens(current_para.trim())));
-                    current_para = String::new();
+                if !current_para.is_blank() {
+                    result.push(Section::Paragraph(current_para.chop_into_tokens(options)));
                 }
+                current_para = StyledText::default();
+                style = Style::default();
             }
 
             _ => { }
         }
 
         match node {
+            Node::Bold { .. } => {
+                style.bold = !style.bold;
+            }
+
+            Node::BoldItalic { .. } => {
+                style.bold = !style.bold;
+                style.italic = !style.italic;
+            }
+
             Node::CharacterEntity { character, .. } => {
-                current_para.push(*character);
+                current_para.push_str(&character.to_string(), &style);
             }
 
             Node::ExternalLink { nodes, ..} => {
@@ -250,24 +417,32 @@ fn get_sections(nodes: &Vec<Node>) -> Vec<Section> {
                     .skip(1)
                     .intersperse(" ")
                     .collect();
-                current_para.push_str(&without_url);
+                current_para.push_str(&without_url, &style);
             }
 
             Node::Heading { level, nodes, .. } => {
                 let heading_text = get_inline_text(nodes);
-                result.push(Section::Heading(*level as usize, chop_into_tokens(&heading_text)));
+                result.push(Section::Heading(*level as usize, chop_into_tokens(&heading_text, options)));
             }
 
-            Node::Link { text: nodes, ..} => {
+            Node::Italic { .. } => {
+                style.italic = !style.italic;
+            }
+
+            Node::Link { target, text: nodes, .. } => {
                 let link_text = get_inline_text(nodes);
-                current_para.push_str(&link_text);
+                let link_style = Style {
+                    link: Some(String::from(*target)),
+                    ..style.clone()
+                };
+                current_para.push_str(&link_text, &link_style);
             }
 
             Node::OrderedList { items, .. } => {
                 let mut sections: Vec<Vec<Section>> = Vec::new();
 
                 for item in items {
-                    sections.push(get_sections(&item.nodes));
+                    sections.push(get_sections(&item.nodes, options));
                 }
 
                 result.push(Section::OrderedList(sections));
@@ -275,18 +450,18 @@ fn get_sections(nodes: &Vec<Node>) -> Vec<Section> {
 
             Node::Template { .. } => {
                 let template_text = get_template_text(node);
-                current_para.push_str(&template_text);
+                current_para.push_str(&template_text, &style);
             }
 
             Node::Text { value, .. } => {
-                current_para.push_str(value);
+                current_para.push_str(value, &style);
             }
 
             Node::UnorderedList { items, .. } => {
                 let mut sections: Vec<Vec<Section>> = Vec::new();
 
                 for item in items {
-                    sections.push(get_sections(&item.nodes));
+                    sections.push(get_sections(&item.nodes, options));
                 }
 
                 result.push(Section::UnorderedList(sections));
@@ -297,14 +472,14 @@ fn get_sections(nodes: &Vec<Node>) -> Vec<Section> {
         }
     }
 
-    if !current_para.trim().is_empty() {
-        result.push(Section::Paragraph(chop_into_tokens(current_para.trim())));
+    if !current_para.is_blank() {
+        result.push(Section::Paragraph(current_para.chop_into_tokens(options)));
     }
 
     result
 }
 
-pub fn parse(title: &str, content: &str) -> WikiArticle {
+pub fn parse(title: &str, content: &str, options: &TokenizerOptions) -> WikiArticle {
 
     let parsed = Configuration::default().parse(content);
 
@@ -315,12 +490,293 @@ pub fn parse(title: &str, content: &str) -> WikiArticle {
         }
     }
 
-    let content = get_sections(&parsed.nodes);
+    let content = get_sections(&parsed.nodes, options);
+
+    let title_tokens = chop_into_tokens(title, options);
+
+    WikiArticle::new(title_tokens, content, options)
+}
+
+// The end of the run of equal characters starting at `start`.
+fn run_end(chars: &[char], start: usize) -> usize {
+    chars[start..]
+        .iter()
+        .position(|c| *c != chars[start])
+        .map(|p| p + start)
+        .unwrap_or(chars.len())
+}
+
+// Where the marker run from `start` to `end` is closed by a run of the same
+// length. Emphasis has to start and end at a word boundary, so "snake_case"
+// and "2*3" are left alone. Code spans close at any matching run.
+fn closing_marker(chars: &[char], start: usize, end: usize) -> Option<usize> {
+    let marker = chars[start];
+    let is_code = marker == '`';
+
+    let opens = is_code
+        || (chars.get(end).map(|c| !c.is_whitespace()).unwrap_or(false)
+            && (start == 0 || !chars[start - 1].is_alphanumeric()));
+    if !opens {
+        return None;
+    }
+
+    let mut i = end;
+    while i < chars.len() {
+        if chars[i] != marker {
+            i += 1;
+            continue;
+        }
+
+        let close_end = run_end(chars, i);
+        let closes = is_code
+            || (!chars[i - 1].is_whitespace()
+                && chars.get(close_end).map(|c| !c.is_alphanumeric()).unwrap_or(true));
+
+        if closes && close_end - i == end - start {
+            return Some(i);
+        }
+        i = close_end;
+    }
+
+    None
+}
+
+fn strip_markdown_inline(line: &str) -> String {
+    let mut result = String::new();
+    let chars: Vec<char> = line.chars().collect();
+    let mut i = 0;
+
+    while i < chars.len() {
+        match chars[i] {
+            // Paired markers are dropped, unpaired ones are kept as text
+            '*' | '_' | '`' => {
+                let end = run_end(&chars, i);
+                let length = end - i;
+
+                match closing_marker(&chars, i, end) {
+                    Some(close) if chars[i] == '`' => {
+                        result.extend(&chars[end..close]);
+                        i = close + length;
+                    }
+
+                    Some(close) => {
+                        let inner: String = chars[end..close].iter().collect();
+                        result.push_str(&strip_markdown_inline(&inner));
+                        i = close + length;
+                    }
+
+                    None => {
+                        result.extend(&chars[i..end]);
+                        i = end;
+                    }
+                }
+            }
+
+            // [link text](target) keeps only the link text
+            '[' => {
+                let close = chars[i..].iter().position(|c| *c == ']').map(|p| p + i);
+
+                match close {
+                    Some(close) if chars.get(close + 1) == Some(&'(') => {
+                        result.extend(&chars[i + 1..close]);
+
+                        let target_end = chars[close..].iter().position(|c| *c == ')').map(|p| p + close);
+                        i = target_end.map(|e| e + 1).unwrap_or(chars.len());
+                    }
+
+                    _ => {
+                        result.push('[');
+                        i += 1;
+                    }
+                }
+            }
+
+            c => {
+                result.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    result
+}
+
+fn get_markdown_list_item(line: &str) -> Option<(bool, &str)> {
+    let trimmed = line.trim_start();
 
-    let title_tokens = chop_into_tokens(title);
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(rest) = trimmed.strip_prefix(marker) {
+            return Some((false, rest));
+        }
+    }
 
-    WikiArticle{
-        title: title_tokens,
-        content: content,
+    let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits > 0 {
+        if let Some(rest) = trimmed[digits..].strip_prefix(". ") {
+            return Some((true, rest));
+        }
     }
+
+    None
+}
+
+fn get_markdown_sections(content: &str, options: &TokenizerOptions) -> Vec<Section> {
+    let mut result: Vec<Section> = Vec::new();
+    let mut current_para = String::new();
+    let mut current_list: Vec<Vec<Section>> = Vec::new();
+    let mut current_list_ordered = false;
+
+    fn end_paragraph(result: &mut Vec<Section>, current_para: &mut String, options: &TokenizerOptions) {
+        if !current_para.trim().is_empty() {
+            result.push(Section::Paragraph(chop_into_tokens(current_para.trim(), options)));
+        }
+        current_para.clear();
+    }
+
+    fn end_list(result: &mut Vec<Section>, current_list: &mut Vec<Vec<Section>>, ordered: bool) {
+        if !current_list.is_empty() {
+            let items = std::mem::take(current_list);
+            if ordered {
+                result.push(Section::OrderedList(items));
+            } else {
+                result.push(Section::UnorderedList(items));
+            }
+        }
+    }
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            end_paragraph(&mut result, &mut current_para, options);
+            end_list(&mut result, &mut current_list, current_list_ordered);
+            continue;
+        }
+
+        if trimmed.starts_with('#') {
+            end_paragraph(&mut result, &mut current_para, options);
+            end_list(&mut result, &mut current_list, current_list_ordered);
+
+            let level = trimmed.chars().take_while(|c| *c == '#').count();
+            let heading_text = strip_markdown_inline(trimmed[level..].trim());
+            result.push(Section::Heading(level + 1, chop_into_tokens(&heading_text, options)));
+            continue;
+        }
+
+        if let Some((ordered, item)) = get_markdown_list_item(line) {
+            end_paragraph(&mut result, &mut current_para, options);
+
+            if ordered != current_list_ordered {
+                end_list(&mut result, &mut current_list, current_list_ordered);
+                current_list_ordered = ordered;
+            }
+
+            let item_text = strip_markdown_inline(item.trim());
+            current_list.push(vec![Section::Paragraph(chop_into_tokens(&item_text, options))]);
+            continue;
+        }
+
+        end_list(&mut result, &mut current_list, current_list_ordered);
+
+        if !current_para.is_empty() {
+            current_para.push(' ');
+        }
+        current_para.push_str(&strip_markdown_inline(trimmed));
+    }
+
+    end_paragraph(&mut result, &mut current_para, options);
+    end_list(&mut result, &mut current_list, current_list_ordered);
+
+    result
+}
+
+fn get_plain_text_sections(content: &str, options: &TokenizerOptions) -> Vec<Section> {
+    let mut result: Vec<Section> = Vec::new();
+    let mut current_para = String::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            if !current_para.is_empty() {
+                result.push(Section::Paragraph(chop_into_tokens(&current_para, options)));
+                current_para.clear();
+            }
+            continue;
+        }
+
+        if !current_para.is_empty() {
+            current_para.push(' ');
+        }
+        current_para.push_str(trimmed);
+    }
+
+    if !current_para.is_empty() {
+        result.push(Section::Paragraph(chop_into_tokens(&current_para, options)));
+    }
+
+    result
+}
+
+// "==Title==" and "# Title #" become "Title". In Markdown a closing marker
+// needs a space before it, so "# C#" stays "C#".
+fn strip_heading_marker(line: &str, marker: char) -> &str {
+    let line = line.trim().trim_start_matches(marker).trim_start();
+    let without_closing = line.trim_end_matches(marker);
+
+    if marker == '=' || without_closing.ends_with(' ') || without_closing.is_empty() {
+        without_closing.trim_end()
+    } else {
+        line
+    }
+}
+
+// The first non-empty line of a puzzle file is its title, the rest is the
+// article body. Heading markers around the title line are ignored so that
+// e.g. "# Title" or "= Title =" work as well.
+pub fn parse_file(path: &Path, options: &TokenizerOptions) -> Result<WikiArticle> {
+    let content = std::fs::read_to_string(path)?;
+
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or(String::from(""));
+
+    // The title may be written as a heading, plain text titles are taken as
+    // they are so that e.g. "C#" keeps its "#".
+    let heading_marker = match extension.as_str() {
+        "wiki" | "wikitext" | "mediawiki" => Some('='),
+        "md" | "markdown" => Some('#'),
+        _ => None,
+    };
+
+    let mut lines = content.lines();
+    let title = lines
+        .by_ref()
+        .map(|line| match heading_marker {
+            Some(marker) => strip_heading_marker(line, marker),
+            None => line.trim(),
+        })
+        .find(|line| !line.is_empty())
+        .ok_or(anyhow!("File {} does not contain a title line", path.display()))?;
+    let title = String::from(title);
+
+    let body: String = lines.intersperse("\n").collect();
+
+    let article = match extension.as_str() {
+        "wiki" | "wikitext" | "mediawiki" => {
+            parse(&title, &body, options)
+        }
+
+        "md" | "markdown" => {
+            WikiArticle::new(chop_into_tokens(&title, options), get_markdown_sections(&body, options), options)
+        }
+
+        _ => {
+            WikiArticle::new(chop_into_tokens(&title, options), get_plain_text_sections(&body, options), options)
+        }
+    };
+
+    Ok(article)
 }
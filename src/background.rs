@@ -0,0 +1,63 @@
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, Result};
+use eframe::egui;
+
+// Lets a task tell the UI how far it got.
+#[derive(Clone)]
+pub struct Progress {
+    message: Arc<Mutex<String>>,
+    ctx: egui::Context,
+}
+
+impl Progress {
+    pub fn set(&self, message: String) {
+        *self.message.lock().unwrap() = message;
+        self.ctx.request_repaint();
+    }
+}
+
+// Work that runs on its own thread, so that slow requests and their retries
+// never block the window. Dropping the task abandons its result.
+pub struct Task<T> {
+    receiver: Receiver<Result<T>>,
+    message: Arc<Mutex<String>>,
+}
+
+impl<T: Send + 'static> Task<T> {
+    pub fn spawn(ctx: &egui::Context, message: String, work: impl FnOnce(&Progress) -> Result<T> + Send + 'static) -> Self {
+        let (sender, receiver) = channel();
+        let message = Arc::new(Mutex::new(message));
+
+        let progress = Progress {
+            message: message.clone(),
+            ctx: ctx.clone(),
+        };
+
+        std::thread::spawn(move || {
+            // The receiver is gone if the task was abandoned meanwhile.
+            let _ = sender.send(work(&progress));
+            progress.ctx.request_repaint();
+        });
+
+        Self {
+            receiver: receiver,
+            message: message,
+        }
+    }
+
+    // The result, once the work is done.
+    pub fn poll(&self) -> Option<Result<T>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Empty) => None,
+            // The thread panicked
+            Err(TryRecvError::Disconnected) => Some(Err(anyhow!("The task stopped unexpectedly"))),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        self.message.lock().unwrap().clone()
+    }
+}
@@ -0,0 +1,89 @@
+use eframe::egui;
+use eframe::epaint::text::LayoutJob;
+use unicode_bidi::{BidiInfo, Level};
+
+pub fn is_rtl(text: &str) -> bool {
+    let bidi_info = BidiInfo::new(text, None);
+
+    bidi_info
+        .paragraphs
+        .first()
+        .map(|para| para.level.is_rtl())
+        .unwrap_or(false)
+}
+
+// Reorders a single line of right-to-left text into visual order.
+pub fn visual_line(text: &str) -> String {
+    let bidi_info = BidiInfo::new(text, Some(Level::rtl()));
+
+    bidi_info
+        .paragraphs
+        .iter()
+        .map(|para| bidi_info.reorder_line(para, para.range.clone()))
+        .collect()
+}
+
+// egui lays out text strictly left-to-right. For a right-to-left paragraph
+// the logical job is wrapped first, then every row is reordered into visual
+// order following the Unicode bidi algorithm, keeping the formatting of each
+// character. Hidden words are neutral underscores, so the paragraph level has
+// to be given explicitly.
+pub fn visual_rows(ui: &egui::Ui, job: LayoutJob) -> Vec<LayoutJob> {
+    let bidi_info = BidiInfo::new(&job.text, Some(Level::rtl()));
+
+    // (byte offset, section index) for every char of the job
+    let chars: Vec<(usize, usize)> = job.text
+        .char_indices()
+        .map(|(offset, _)| {
+            let section = job.sections
+                .iter()
+                .position(|section| section.byte_range.contains(&offset))
+                .unwrap_or(0);
+            (offset, section)
+        })
+        .collect();
+
+    let galley = ui.fonts(|fonts| fonts.layout_job(job.clone()));
+
+    let mut result = Vec::new();
+    let mut row_start = 0;
+
+    for row in &galley.rows {
+        let row_end = (row_start + row.glyphs.len()).min(chars.len());
+        let row_chars = &chars[row_start..row_end];
+
+        let levels: Vec<Level> = row_chars
+            .iter()
+            .map(|(offset, _)| bidi_info.levels[*offset])
+            .collect();
+
+        let mut row_job = LayoutJob::default();
+        let mut run = String::new();
+        let mut run_section = None;
+
+        for index in BidiInfo::reorder_visual(&levels) {
+            let (offset, section) = row_chars[index];
+
+            if run_section != Some(section) {
+                if let Some(previous) = run_section {
+                    row_job.append(&run, 0.0, job.sections[previous].format.clone());
+                    run.clear();
+                }
+                run_section = Some(section);
+            }
+
+            if let Some(c) = job.text[offset..].chars().next() {
+                run.push(c);
+            }
+        }
+
+        if let Some(previous) = run_section {
+            row_job.append(&run, 0.0, job.sections[previous].format.clone());
+        }
+
+        result.push(row_job);
+        row_start = row_end + if row.ends_with_newline { 1 } else { 0 };
+    }
+
+    result
+}
@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+pub fn config_file(name: &str) -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().ok_or(anyhow!("Could not determine config directory"))?;
+    Ok(config_dir.join("rsdctl").join(name))
+}
+
+// Returns None if the file does not exist yet.
+pub fn load_json<T: DeserializeOwned>(name: &str) -> Result<Option<T>> {
+    let path = config_file(name)?;
+
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+pub fn save_json<T: Serialize>(name: &str, value: &T) -> Result<()> {
+    let path = config_file(name)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let content = serde_json::to_string_pretty(value)?;
+    std::fs::write(&path, content)?;
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
+
+use crate::article_parser::WikiArticle;
+
+const COMMON_WORDS_EN: &str = include_str!("../assets/common_words_en.txt");
+
+// Scores are between 0 (trivial) and 100 (very hard).
+pub const MIN_SCORE: u32 = 0;
+pub const MAX_SCORE: u32 = 100;
+
+fn common_words_en() -> &'static BTreeSet<&'static str> {
+    static WORDS: OnceLock<BTreeSet<&'static str>> = OnceLock::new();
+
+    WORDS.get_or_init(|| {
+        COMMON_WORDS_EN
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect()
+    })
+}
+
+// Without a frequency list for the language, long words are taken as rare.
+fn is_rare(word: &str, language: &str) -> bool {
+    if language == "en" || language == "simple" {
+        !common_words_en().contains(word)
+    } else {
+        word.chars().count() > 7
+    }
+}
+
+// Each factor is between 0 (makes the article easier) and 1 (harder).
+pub fn estimate(article: &WikiArticle, language: &str) -> u32 {
+    let counts = article.content_word_counts();
+    let title_words = article.title_words();
+
+    // Short articles give few clues.
+    let total_words: usize = counts.values().sum();
+    let length = 1.0 - ((total_words as f32 + 1.0).ln() / 5000f32.ln()).min(1.0);
+
+    // Title words that rarely occur in the body are hard to find.
+    let title_frequency = if title_words.is_empty() {
+        1.0
+    } else {
+        let average: f32 = title_words
+            .iter()
+            .map(|word| (*counts.get(word).unwrap_or(&0)).min(20) as f32 / 20.0)
+            .sum::<f32>() / title_words.len() as f32;
+        1.0 - average
+    };
+
+    // Uncommon title words are unlikely to be guessed by chance.
+    let rarity = if title_words.is_empty() {
+        0.0
+    } else {
+        title_words.iter().filter(|word| is_rare(word, language)).count() as f32
+            / title_words.len() as f32
+    };
+
+    // Headings reveal the structure of the article.
+    let headings = 1.0 - article.chapter_count().min(20) as f32 / 20.0;
+
+    let score = 0.25 * length + 0.35 * title_frequency + 0.25 * rarity + 0.15 * headings;
+
+    ((score * MAX_SCORE as f32).round() as u32).clamp(MIN_SCORE, MAX_SCORE)
+}
+
+pub fn label(score: u32) -> &'static str {
+    match score {
+        0..=24 => "easy",
+        25..=49 => "medium",
+        50..=74 => "hard",
+        _ => "very hard",
+    }
+}
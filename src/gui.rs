@@ -1,83 +1,1129 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 
 use eframe::egui;
 use eframe::egui::widgets::*;
-use eframe::epaint::{Color32, text::{LayoutJob, TextFormat}};
+use eframe::egui::{Key, KeyboardShortcut, Modifiers};
+use eframe::epaint::{Stroke, text::{LayoutJob, TextFormat}};
 use egui_notify::{Toasts};
 
+use crate::appearance;
+use crate::appearance::{Appearance, ArticleFont, Theme};
+use crate::article_format;
 use crate::article_parser;
-use crate::article_parser::{WikiArticle, Token, Section};
+use crate::article_parser::{WikiArticle, Token, Section, Chapter, Style};
+use crate::background::Task;
+use crate::bidi_layout;
+use crate::difficulty;
+use crate::history;
+use crate::history::GameRecord;
+use crate::http_client::FetchError;
+use crate::languages;
+use crate::languages::Language;
+use crate::phrase;
+use crate::puzzle_pack::{PackProgress, PuzzlePack};
+use crate::spelling;
+use crate::spelling::WordList;
+use crate::title_search;
+use crate::title_search::TitleSearch;
+use crate::tokenizer::{chop_into_tokens, NumberRule, TokenizerOptions};
+use crate::wiki_endpoint;
+use crate::wiki_endpoint::WikiEndpoint;
 use crate::wikipedia_api;
+use crate::word_vectors::WordVectors;
+use crate::wikipedia_api::ArticleError;
+
+struct ArticleChooser {
+    heading: String,
+    options: Vec<String>,
+}
+
+const MAX_RANDOM_ATTEMPTS: usize = 10;
+
+struct ArticleActions {
+    // Whether the game is won, worked out once per frame
+    reveal_all: bool,
+    unlock_next: bool,
+    reached_scroll_target: bool,
+    follow_link: Option<String>,
+    // Occurrences of the selected guess in the shown paragraphs
+    occurrences: usize,
+}
+
+// What to do with an article once its download finished
+enum AfterLoad {
+    Play,
+    // A random article, which may be outside of the difficulty range.
+    Random,
+    PackEntry(usize),
+}
+
+struct Loading {
+    after: AfterLoad,
+    // Where the article is downloaded from
+    endpoint: WikiEndpoint,
+    language: String,
+    // (title, article)
+    task: Task<(String, WikiArticle)>,
+}
+
+// Step by step playback of a game from the history.
+struct Replay {
+    record: GameRecord,
+    // Number of guesses applied so far
+    step: usize,
+    playing: bool,
+    last_step_at: Instant,
+}
+
+const REPLAY_STEP_INTERVAL: Duration = Duration::from_secs(1);
+
+const FOCUS_GUESS_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::G);
+const RANDOM_ARTICLE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::R);
+const NEXT_GUESS_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::ALT, Key::ArrowDown);
+const PREVIOUS_GUESS_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::ALT, Key::ArrowUp);
+const TOGGLE_HIGHLIGHT_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::H);
+const NEXT_OCCURRENCE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F3);
+const PREVIOUS_OCCURRENCE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::SHIFT, Key::F3);
+const UNDO_GUESS_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+const SETTINGS_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F2);
+const SHORTCUTS_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::F1);
+const CLOSE_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::NONE, Key::Escape);
+
+// For the cheat sheet
+const SHORTCUTS: [(KeyboardShortcut, &str); 10] = [
+    (FOCUS_GUESS_SHORTCUT, "Focus the guess box"),
+    (RANDOM_ARTICLE_SHORTCUT, "Load a random article"),
+    (NEXT_GUESS_SHORTCUT, "Select the next guess"),
+    (PREVIOUS_GUESS_SHORTCUT, "Select the previous guess"),
+    (TOGGLE_HIGHLIGHT_SHORTCUT, "Toggle highlighting of the selected guess"),
+    (NEXT_OCCURRENCE_SHORTCUT, "Jump to the next occurrence of the selected guess"),
+    (PREVIOUS_OCCURRENCE_SHORTCUT, "Jump to the previous occurrence"),
+    (UNDO_GUESS_SHORTCUT, "Undo the last guess (when the guess box is empty)"),
+    (SETTINGS_SHORTCUT, "Open the settings"),
+    (SHORTCUTS_SHORTCUT, "Show this cheat sheet"),
+];
+
+struct PackCreator {
+    title: String,
+    titles: String,
+    include_articles: bool,
+    creating: Option<Task<()>>,
+}
 
 struct App {
+    egui_ctx: egui::Context,
+    endpoints: Vec<WikiEndpoint>,
+    selected_endpoint: usize,
+    show_endpoint_settings: bool,
+    languages: Vec<Language>,
+    language_filter: String,
+    show_language_window: bool,
+    languages_refresh: Option<Task<Vec<Language>>>,
     selected_language: String,
+    default_language: String,
+    tokenizer_options: TokenizerOptions,
     wiki_article: Option<WikiArticle>,
+    // The wiki and language the article came from, which need not be the
+    // selected ones
+    article_endpoint: WikiEndpoint,
+    article_language: String,
     guesses: BTreeSet<String>,
+    // The same guesses in the order they were made
+    guess_log: Vec<String>,
+    game_started: Instant,
+    game_recorded: bool,
+    history: Vec<GameRecord>,
+    show_statistics: bool,
+    show_settings: bool,
+    appearance: Appearance,
+    show_shortcuts: bool,
+    // The highlighted guess while highlighting is toggled off
+    hidden_highlight: String,
+    current_occurrence: Option<usize>,
+    jump_to_occurrence: Option<usize>,
+    occurrence_count: usize,
+    scroll_to_selected_guess: bool,
+    replay: Option<Replay>,
+    loading: Option<Loading>,
+
+    title_text_box: String,
+    title_search: TitleSearch,
+    known_titles: Vec<String>,
+    toasts: Toasts,
+    next_guess: String,
+    // A guess that was warned about and is accepted if entered again
+    unconfirmed_guess: Option<String>,
+    check_typos: bool,
+    phrase_guesses: bool,
+    require_known_words: bool,
+    word_list_paths: BTreeMap<String, PathBuf>,
+    // Word list of the language it was loaded for
+    word_list: Option<(String, WordList)>,
+    selected_guess: String,
+    focus_on_guess: bool,
+    article_chooser: Option<ArticleChooser>,
+    hints_used: usize,
+    progressive_reveal: bool,
+    guesses_per_unlock: usize,
+    extra_unlocks: usize,
+    article_generation: usize,
+    show_toc: bool,
+    underline_links: bool,
+    // Hidden words are named for screen readers and guess results are
+    // announced
+    accessible_mode: bool,
+    announcement: String,
+    scroll_to_chapter: Option<usize>,
+    current_difficulty: Option<u32>,
+    random_difficulty: (u32, u32),
+
+    pack: Option<PuzzlePack>,
+    pack_entry: Option<usize>,
+    pack_progress: PackProgress,
+    show_pack_creator: bool,
+    pack_creator: PackCreator,
+
+    show_closeness: bool,
+    word_vectors_path: Option<PathBuf>,
+    word_vectors: Option<WordVectors>,
+    word_vectors_loading: Option<Task<WordVectors>>,
+}
+
+impl App {
+    fn start_game(&mut self, wiki_article: WikiArticle, endpoint: WikiEndpoint, language: &str) {
+        self.record_abandoned_game();
+
+        self.wiki_article = Some(wiki_article);
+        self.article_endpoint = endpoint;
+        self.article_language = String::from(language);
+        self.replay = None;
+        self.article_chooser = None;
+        self.hints_used = 0;
+        self.extra_unlocks = 0;
+        self.article_generation += 1;
+        self.scroll_to_chapter = None;
+        self.pack_entry = None;
+        self.current_difficulty = self.wiki_article
+            .as_ref()
+            .map(|wiki_article| difficulty::estimate(wiki_article, &self.article_language));
+        self.guesses.clear();
+        self.guess_log.clear();
+        self.unconfirmed_guess = None;
+        self.announcement.clear();
+        self.game_started = Instant::now();
+        self.game_recorded = false;
+        self.next_guess.clear();
+        self.title_text_box.clear();
+        self.title_search.clear();
+        self.selected_guess.clear();
+    }
+
+    fn add_guess(&mut self, guess: String) {
+        if self.guesses.insert(guess.clone()) {
+            self.guess_log.push(guess);
+        }
+    }
+
+    fn remove_guess(&mut self, guess: &str) {
+        self.guesses.remove(guess);
+        self.guess_log.retain(|g| g != guess);
+
+        if self.selected_guess == guess {
+            self.selected_guess.clear();
+        }
+    }
+
+    fn undo_guess(&mut self) {
+        if let Some(last) = self.guess_log.last().cloned() {
+            self.remove_guess(&last);
+            self.announce(format!("Removed \"{}\"", last));
+        }
+    }
+
+    fn hits_text(&self, guess: &str) -> String {
+        match self.count_word_in_article(guess) {
+            0 => format!("\"{}\": no hits", guess),
+            1 => format!("\"{}\": 1 hit", guess),
+            hits => format!("\"{}\": {} hits", guess, hits),
+        }
+    }
+
+    // Read out by screen readers in accessible mode, since a guess changes
+    // nothing that has focus.
+    fn announce(&mut self, announcement: String) {
+        self.announcement = match &self.wiki_article {
+            Some(wiki_article) if self.title_complete() => {
+                format!("{}. Solved, the article is {}", announcement, Self::tokens_text(&wiki_article.title))
+            }
+            _ => announcement,
+        };
+    }
+
+    // The dictionary is the word list of the language if there is one,
+    // otherwise the vocabulary of the word vectors, when they are loaded.
+    fn suspicious_guess(&self, guess: &str) -> Option<String> {
+        let word_list = self.word_list
+            .as_ref()
+            .filter(|(language, _)| *language == self.article_language)
+            .map(|(_, word_list)| word_list);
+
+        let is_known = |word: &str| match (word_list, &self.word_vectors) {
+            (Some(word_list), _) => word_list.contains(word),
+            (None, Some(word_vectors)) => word_vectors.contains(word),
+            (None, None) => true,
+        };
+
+        let words = self.guess_words(guess);
+        spelling::suspicious_guess(guess, &words, &self.guess_log, |word| self.count_word_in_article(word) > 0, is_known)
+    }
+
+    // The input split into words the same way as the article, so that
+    // punctuation around a guess is ignored. The options may have changed
+    // since the article was loaded.
+    fn guess_tokens(&self, input: &str) -> Vec<Token> {
+        let options = self.wiki_article
+            .as_ref()
+            .map(|wiki_article| &wiki_article.tokenizer_options)
+            .unwrap_or(&self.tokenizer_options);
+
+        chop_into_tokens(input, options)
+    }
+
+    fn guess_words(&self, input: &str) -> Vec<String> {
+        self.guess_tokens(input)
+            .into_iter()
+            .filter_map(|token| match token {
+                Token::Word(w, _) => Some(w.to_lowercase()),
+                Token::NonWord(..) => None,
+            })
+            .collect()
+    }
+
+    // Several words are guessed one by one, unless they are a quoted phrase
+    // and phrase guesses are enabled.
+    fn parse_guesses(&self, input: &str) -> Vec<String> {
+        if self.phrase_guesses {
+            if let Some(quoted) = phrase::parse_quoted(input) {
+                let phrase = phrase::phrase_text(&self.guess_tokens(&quoted));
+                return if phrase.is_empty() { Vec::new() } else { vec![phrase] };
+            }
+        }
+
+        self.guess_words(input)
+    }
+
+    // The word list of the language of the article
+    fn load_word_list(&mut self) {
+        let loaded = self.word_list
+            .as_ref()
+            .map(|(language, _)| *language == self.article_language)
+            .unwrap_or(false);
+
+        if loaded {
+            return;
+        }
+
+        self.word_list = None;
+
+        let Some(path) = self.word_list_paths.get(&self.article_language) else {
+            return;
+        };
+
+        match WordList::load(path) {
+            Ok(word_list) => {
+                self.word_list = Some((self.article_language.clone(), word_list));
+            }
+
+            Err(e) => {
+                self.toasts.error(format!("Could not load word list {}: {}", path.display(), e));
+            }
+        }
+    }
+
+    // Words that are neither in the word list of the language nor in the
+    // article. Accepting article words keeps names and rare terms guessable.
+    fn unknown_words(&mut self, guesses: &[String]) -> Vec<String> {
+        self.load_word_list();
+
+        let Some((_, word_list)) = &self.word_list else {
+            return Vec::new();
+        };
+
+        guesses
+            .iter()
+            .flat_map(|guess| self.guess_words(guess))
+            .filter(|word| !word.chars().all(|c| c.is_numeric()))
+            .filter(|word| !word_list.contains(word) && self.count_word_in_article(word) == 0)
+            .collect()
+    }
+
+    fn submit_guess(&mut self) {
+        let input = self.next_guess.trim().to_lowercase();
+
+        if input.is_empty() {
+            return;
+        }
+
+        let (repeated, guesses): (Vec<String>, Vec<String>) = self
+            .parse_guesses(&input)
+            .into_iter()
+            .partition(|guess| self.guesses.contains(guess));
+
+        if repeated.is_empty() && guesses.is_empty() {
+            self.toasts.error(format!("\"{}\" does not contain any word", input));
+            return;
+        }
+
+        if !repeated.is_empty() {
+            self.toasts.info(format!("Already guessed: {}", repeated.join(", ")));
+        }
+
+        if guesses.is_empty() {
+            self.next_guess.clear();
+            return;
+        }
+
+        if self.require_known_words {
+            let unknown = self.unknown_words(&guesses);
+
+            if !unknown.is_empty() {
+                self.toasts.error(format!("Not in the word list: {}", unknown.join(", ")));
+                return;
+            }
+        }
+
+        let warned_before = self.unconfirmed_guess.as_ref() == Some(&input);
+
+        if self.check_typos && !warned_before {
+            self.load_word_list();
+
+            let warnings: Vec<String> = guesses
+                .iter()
+                .filter_map(|guess| self.suspicious_guess(guess))
+                .collect();
+
+            if !warnings.is_empty() {
+                self.toasts.warning(format!("{}. Press Enter again to guess anyway.", warnings.join(", ")));
+                self.unconfirmed_guess = Some(input);
+                return;
+            }
+        }
+
+        let hits: Vec<String> = guesses.iter().map(|guess| self.hits_text(guess)).collect();
+        for guess in guesses {
+            self.add_guess(guess);
+        }
+        self.announce(hits.join(", "));
+        self.unconfirmed_guess = None;
+        self.next_guess.clear();
+    }
+
+    fn record_game(&mut self, solved: bool) {
+        let Some(wiki_article) = &self.wiki_article else {
+            return;
+        };
+
+        let hits = self.guess_log
+            .iter()
+            .filter(|guess| self.count_word_in_article(guess) > 0)
+            .count();
+
+        let finished_at = history::now();
+
+        let article_file = match history::save_article(finished_at, wiki_article) {
+            Ok(file_name) => Some(file_name),
+
+            Err(e) => {
+                self.toasts.error(format!("Could not save the article of the game: {}", e));
+                None
+            }
+        };
+
+        let record = GameRecord {
+            title: Self::tokens_text(&wiki_article.title),
+            language: self.article_language.clone(),
+            endpoint: self.article_endpoint.name.clone(),
+            solved: solved,
+            guesses: self.guess_log.clone(),
+            hits: hits,
+            hints_used: self.hints_used,
+            duration_secs: self.game_started.elapsed().as_secs(),
+            finished_at: finished_at,
+            article_file: article_file,
+        };
+
+        self.game_recorded = true;
+
+        if let Err(e) = history::append(&record) {
+            self.toasts.error(format!("Could not save game history: {}", e));
+        }
+        self.history.push(record);
+    }
+
+    // A game that was left unfinished counts as abandoned.
+    fn record_abandoned_game(&mut self) {
+        if !self.game_recorded && !self.guess_log.is_empty() {
+            self.record_game(false);
+        }
+    }
+
+    fn update_history(&mut self) {
+        if !self.game_recorded && self.title_complete() {
+            self.record_game(true);
+        }
+    }
+
+    // A configured or built-in wiki
+    fn endpoint_named(&self, name: &str) -> Option<WikiEndpoint> {
+        self.endpoints
+            .iter()
+            .cloned()
+            .chain(wiki_endpoint::default_endpoints())
+            .find(|endpoint| endpoint.name == name)
+    }
+
+    // Starts a new game on the saved article of a past game. Games recorded
+    // before articles were saved are downloaded again.
+    fn play_again(&mut self, record: &GameRecord) {
+        let endpoint = self.endpoint_named(&record.endpoint);
+
+        match (history::load_article(record), endpoint) {
+            (Ok(Some(article)), endpoint) => {
+                let endpoint = endpoint.unwrap_or(self.endpoints[self.selected_endpoint].clone());
+                self.start_game(article, endpoint, &record.language);
+            }
+
+            (Ok(None), Some(endpoint)) => {
+                self.download_article_from(endpoint, record.language.clone(), &record.title, AfterLoad::Play);
+            }
+
+            (Ok(None), None) => {
+                self.toasts.error(format!("\"{}\" was not saved and {} is no longer configured", record.title, record.endpoint));
+            }
+
+            (Err(e), _) => {
+                self.toasts.error(format!("Could not load the saved article of \"{}\": {}", record.title, e));
+            }
+        }
+    }
+
+    // Replays a past game on the article it was played on, which is revealed
+    // one guess at a time. The replay is never recorded as a game of its own.
+    fn start_replay(&mut self, record: &GameRecord) {
+        self.show_statistics = false;
+
+        match history::load_article(record) {
+            Ok(Some(article)) => {
+                let endpoint = self.endpoint_named(&record.endpoint)
+                    .unwrap_or(self.endpoints[self.selected_endpoint].clone());
+                self.start_game(article, endpoint, &record.language);
+                self.begin_replay(record.clone());
+            }
+
+            // The article may have changed since, so the guesses would not
+            // reveal the same words.
+            Ok(None) => {
+                self.toasts.error(format!("The article of \"{}\" was not saved with the game, so it can't be replayed", record.title));
+            }
+
+            Err(e) => {
+                self.toasts.error(format!("Could not load the saved article of \"{}\": {}", record.title, e));
+            }
+        }
+    }
+
+    fn begin_replay(&mut self, record: GameRecord) {
+        self.game_recorded = true;
+        self.replay = Some(Replay {
+            record: record,
+            step: 0,
+            playing: false,
+            last_step_at: Instant::now(),
+        });
+    }
+
+    fn update_replay(&mut self, ctx: &egui::Context) {
+        let Some(replay) = &mut self.replay else {
+            return;
+        };
+
+        if replay.playing {
+            let elapsed = replay.last_step_at.elapsed();
+
+            if replay.step >= replay.record.guesses.len() {
+                replay.playing = false;
+            } else if elapsed >= REPLAY_STEP_INTERVAL {
+                replay.step += 1;
+                replay.last_step_at = Instant::now();
+                ctx.request_repaint_after(REPLAY_STEP_INTERVAL);
+            } else {
+                ctx.request_repaint_after(REPLAY_STEP_INTERVAL - elapsed);
+            }
+        }
+
+        if replay.step == self.guess_log.len() {
+            return;
+        }
+
+        let applied = &replay.record.guesses[..replay.step];
+        self.guesses = applied.iter().cloned().collect();
+        self.guess_log = applied.to_vec();
+        self.selected_guess = applied.last().cloned().unwrap_or(String::from(""));
+    }
+
+    fn show_replay_controls(&mut self, ui: &mut egui::Ui) {
+        let mut stop = false;
+
+        if let Some(replay) = &mut self.replay {
+            let total = replay.record.guesses.len();
+
+            ui.horizontal(|ui| {
+                ui.label(format!("Replay of {} ({})", replay.record.title, history::format_date(replay.record.finished_at)));
+
+                if ui.button("⏮").on_hover_text("First step").clicked() {
+                    replay.step = 0;
+                    replay.playing = false;
+                }
+
+                if ui.add_enabled(replay.step > 0, Button::new("◀")).on_hover_text("Previous guess").clicked() {
+                    replay.step -= 1;
+                    replay.playing = false;
+                }
+
+                let play_label = if replay.playing { "⏸" } else { "▶" };
+                if ui.button(play_label).on_hover_text("Play").clicked() {
+                    replay.playing = !replay.playing;
+                    replay.last_step_at = Instant::now();
+                }
+
+                if ui.add_enabled(replay.step < total, Button::new("▶|")).on_hover_text("Next guess").clicked() {
+                    replay.step += 1;
+                    replay.playing = false;
+                }
+
+                ui.add(Slider::new(&mut replay.step, 0..=total).text(format!("of {} guesses", total)));
+
+                if ui.button("stop replay").clicked() {
+                    stop = true;
+                }
+            });
+        }
+
+        // What the current step revealed
+        if let Some(guess) = self.guess_log.last() {
+            let occurs = self.count_word_in_article(guess);
+            ui.label(format!("Guess {}: \"{}\" revealed {} occurrences", self.guess_log.len(), guess, occurs));
+        }
+
+        if stop {
+            self.replay = None;
+        }
+    }
+
+    fn report_fetch_error(&mut self, e: anyhow::Error) {
+        match e.downcast_ref::<ArticleError>() {
+            Some(ArticleError::Disambiguation { title, options }) => {
+                self.article_chooser = Some(ArticleChooser {
+                    heading: format!("\"{}\" may refer to:", title),
+                    options: options.clone(),
+                });
+                return;
+            }
+
+            Some(ArticleError::Missing { suggestions, .. }) if !suggestions.is_empty() => {
+                self.article_chooser = Some(ArticleChooser {
+                    heading: String::from("Did you mean:"),
+                    options: suggestions.clone(),
+                });
+            }
+
+            _ => { }
+        }
+
+        match e.downcast_ref::<FetchError>() {
+            Some(FetchError::RateLimited(_)) | Some(FetchError::Network(_)) => {
+                self.toasts.warning(format!("{}", e));
+            }
+
+            _ => {
+                self.toasts.error(format!("{}", e));
+            }
+        }
+    }
+
+    fn show_article_chooser(&mut self, ctx: &egui::Context) {
+        let Some(chooser) = &self.article_chooser else {
+            return;
+        };
+
+        let mut open = true;
+        let mut chosen = None;
+
+        egui::Window::new("Choose an article")
+            .open(&mut open)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                ui.label(chooser.heading.as_str());
+
+                for option in &chooser.options {
+                    if ui.link(option.as_str()).clicked() {
+                        chosen = Some(option.clone());
+                    }
+                }
+            });
+
+        if let Some(title) = chosen {
+            self.article_chooser = None;
+            self.title_text_box = title;
+            self.load_article();
+        } else if !open {
+            self.article_chooser = None;
+        }
+    }
+
+    fn remember_title(&mut self, title: String) {
+        if self.known_titles.contains(&title) {
+            return;
+        }
+
+        self.known_titles.push(title);
+
+        if let Err(e) = title_search::save_known_titles(&self.known_titles) {
+            self.toasts.error(format!("Could not save article titles: {}", e));
+        }
+    }
+
+    fn load_article(&mut self) {
+        let title = self.title_text_box.clone();
+        self.download_article(&title, AfterLoad::Play);
+    }
+
+    // Downloads and parses the article in the background, a download that is
+    // still running is abandoned.
+    fn download_article(&mut self, title: &str, after: AfterLoad) {
+        let endpoint = self.endpoints[self.selected_endpoint].clone();
+        let language = self.selected_language.clone();
+        self.download_article_from(endpoint, language, title, after);
+    }
+
+    fn download_article_from(&mut self, endpoint: WikiEndpoint, language: String, title: &str, after: AfterLoad) {
+        let title = String::from(title);
+        let options = self.tokenizer_options.clone();
+
+        let task_endpoint = endpoint.clone();
+        let task_language = language.clone();
+
+        let task = Task::spawn(&self.egui_ctx, format!("Loading \"{}\"…", title), move |_| {
+            let (title, content) = wikipedia_api::download_article(&task_endpoint, &task_language, &title)?;
+            let article = article_parser::parse(&title, &content, &options);
+            Ok((title, article))
+        });
+
+        self.loading = Some(Loading {
+            after: after,
+            endpoint: endpoint,
+            language: language,
+            task: task,
+        });
+    }
+
+    fn after_load(&mut self, after: AfterLoad) {
+        match after {
+            AfterLoad::Play => { }
+            AfterLoad::Random => {
+                let (min, max) = self.random_difficulty;
+                let score = self.current_difficulty.unwrap_or(min);
+
+                if score < min || score > max {
+                    self.toasts.info("No article in the difficulty range was found, using the closest one");
+                }
+            }
+            AfterLoad::PackEntry(index) => self.pack_entry = Some(index),
+        }
+    }
+
+    fn poll_loading(&mut self) {
+        let Some(result) = self.loading.as_ref().and_then(|loading| loading.task.poll()) else {
+            return;
+        };
+
+        let Some(loading) = self.loading.take() else {
+            return;
+        };
+
+        match result {
+            Ok((title, article)) => {
+                self.start_game(article, loading.endpoint, &loading.language);
+
+                self.remember_title(title);
+
+                self.after_load(loading.after);
+            }
+
+            Err(e) => {
+                self.report_fetch_error(e);
+            }
+        }
+    }
+
+    // Starts a new game on the target of a link in the current article, which
+    // may point to a section ("Page#Section") or start with a colon.
+    fn follow_link(&mut self, target: &str) {
+        let title = target
+            .split('#')
+            .next()
+            .unwrap_or("")
+            .trim_start_matches(':')
+            .trim();
+
+        if title.is_empty() {
+            return;
+        }
+
+        // The link points into the wiki the article came from.
+        let endpoint = self.article_endpoint.clone();
+        let language = self.article_language.clone();
+        self.download_article_from(endpoint, language, title, AfterLoad::Play);
+    }
+
+    // Random articles come from the English Wikipedia, which may have been
+    // moved or removed in the endpoint editor.
+    // Tries a few random articles in the background and keeps the one closest
+    // to the difficulty range. They always come from the English Wikipedia,
+    // the selected wiki and language are left alone.
+    fn load_random_article(&mut self) {
+        let endpoint = wiki_endpoint::wikipedia();
+        let task_endpoint = endpoint.clone();
+        let (min, max) = self.random_difficulty;
+        let options = self.tokenizer_options.clone();
+
+        let task = Task::spawn(&self.egui_ctx, String::from("Looking for a random article…"), move |progress| {
+            // (distance from the target range, title, article)
+            let mut closest: Option<(u32, String, WikiArticle)> = None;
+
+            for attempt in 1..=MAX_RANDOM_ATTEMPTS {
+                progress.set(format!("Looking for a random article ({} of {})…", attempt, MAX_RANDOM_ATTEMPTS));
+
+                let name = wikipedia_api::random_english_article()?;
+                let (title, content) = wikipedia_api::download_article(&task_endpoint, "en", &name)?;
+
+                let article = article_parser::parse(&title, &content, &options);
+                let score = difficulty::estimate(&article, "en");
+                let distance = min.saturating_sub(score) + score.saturating_sub(max);
+
+                if closest.as_ref().map(|(d, ..)| distance < *d).unwrap_or(true) {
+                    closest = Some((distance, title, article));
+                }
+
+                if distance == 0 {
+                    break;
+                }
+            }
+
+            let (_, title, article) = closest.ok_or(anyhow::anyhow!("No random article was found"))?;
+            Ok((title, article))
+        });
+
+        self.loading = Some(Loading {
+            after: AfterLoad::Random,
+            endpoint: endpoint,
+            language: String::from("en"),
+            task: task,
+        });
+    }
+
+    fn open_file(&mut self) {
+        let path = rfd::FileDialog::new()
+            .add_filter("Puzzle files", &["wiki", "wikitext", "mediawiki", "md", "markdown", "txt", "json", "rsda"])
+            .add_filter("All files", &["*"])
+            .pick_file();
+
+        let Some(path) = path else {
+            return;
+        };
+
+        let loaded = if article_format::is_article_file(&path) {
+            article_format::load(&path)
+        } else {
+            article_parser::parse_file(&path, &self.tokenizer_options)
+        };
+
+        match loaded {
+            Ok(wiki_article) => {
+                let endpoint = self.endpoints[self.selected_endpoint].clone();
+                let language = self.selected_language.clone();
+                self.start_game(wiki_article, endpoint, &language);
+            }
+
+            Err(e) => {
+                self.toasts.error(format!("{}", e));
+            }
+        }
+    }
+
+    fn save_article(&mut self) {
+        let Some(wiki_article) = &self.wiki_article else {
+            return;
+        };
+
+        let path = rfd::FileDialog::new()
+            .add_filter("JSON article", &["json"])
+            .add_filter("Binary article", &["rsda"])
+            .save_file();
+
+        let Some(path) = path else {
+            return;
+        };
+
+        if let Err(e) = article_format::save(&path, wiki_article) {
+            self.toasts.error(format!("{}", e));
+        }
+    }
+
+    fn open_pack(&mut self) {
+        let path = rfd::FileDialog::new()
+            .add_filter("Puzzle pack", &["json"])
+            .pick_file();
+
+        let Some(path) = path else {
+            return;
+        };
+
+        match PuzzlePack::load(&path) {
+            Ok(pack) => {
+                let first_unsolved = (0..pack.entries.len())
+                    .find(|i| !self.pack_progress.is_solved(&pack, *i))
+                    .unwrap_or(0);
+
+                self.pack = Some(pack);
+                self.play_pack_entry(first_unsolved);
+            }
+
+            Err(e) => {
+                self.toasts.error(format!("{}", e));
+            }
+        }
+    }
+
+    fn play_pack_entry(&mut self, index: usize) {
+        let Some(pack) = &self.pack else {
+            return;
+        };
+
+        let Some(entry) = pack.entries.get(index) else {
+            return;
+        };
+
+        let (article, title, language) = (entry.article.clone(), entry.title.clone(), pack.language.clone());
+
+        // Entries come from the wiki the pack was made from, whichever is
+        // selected.
+        let endpoint = pack.wiki_endpoint(&self.endpoints);
+
+        match article {
+            Some(article) => {
+                self.start_game(article, endpoint, &language);
+                self.pack_entry = Some(index);
+            }
+
+            None => {
+                self.download_article_from(endpoint, language, &title, AfterLoad::PackEntry(index));
+            }
+        }
+    }
+
+    fn create_pack(&mut self) {
+        let titles: Vec<String> = self.pack_creator.titles
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect();
+
+        if titles.is_empty() {
+            self.toasts.error("Please enter at least one article title");
+            return;
+        }
+
+        let path = rfd::FileDialog::new()
+            .add_filter("Puzzle pack", &["json"])
+            .save_file();
+
+        let Some(path) = path else {
+            return;
+        };
+
+        let pack_title = self.pack_creator.title.clone();
+        let endpoint = self.endpoints[self.selected_endpoint].clone();
+        let language = self.selected_language.clone();
+        let options = self.tokenizer_options.clone();
+        let include_articles = self.pack_creator.include_articles;
+
+        let task = Task::spawn(&self.egui_ctx, String::from("Creating puzzle pack…"), move |progress| {
+            let pack = PuzzlePack::create(&pack_title, &endpoint, &language, &titles, &options, include_articles, |i| {
+                progress.set(format!("Downloading article {} of {}…", i + 1, titles.len()));
+            })?;
+            pack.save(&path)
+        });
+
+        self.pack_creator.creating = Some(task);
+    }
+
+    fn poll_pack_creation(&mut self) {
+        let Some(result) = self.pack_creator.creating.as_ref().and_then(|task| task.poll()) else {
+            return;
+        };
+
+        self.pack_creator.creating = None;
+
+        match result {
+            Ok(()) => {
+                self.toasts.success("Puzzle pack saved");
+                self.show_pack_creator = false;
+            }
+
+            Err(e) => {
+                self.report_fetch_error(e);
+            }
+        }
+    }
+
+    fn update_pack_progress(&mut self) {
+        let (Some(pack), Some(index)) = (&self.pack, self.pack_entry) else {
+            return;
+        };
+
+        if !self.title_complete() || self.pack_progress.is_solved(pack, index) {
+            return;
+        }
+
+        self.pack_progress.mark_solved(pack, index);
+        self.toasts.success(format!(
+            "Solved {} of {} in \"{}\"",
+            self.pack_progress.solved_count(pack),
+            pack.entries.len(),
+            pack.title));
+
+        if let Err(e) = self.pack_progress.save() {
+            self.toasts.error(format!("Could not save puzzle pack progress: {}", e));
+        }
+    }
+
+    fn allowed_hints(&self) -> Option<usize> {
+        let (Some(pack), Some(index)) = (&self.pack, self.pack_entry) else {
+            return None;
+        };
+
+        pack.entries[index].allowed_hints
+    }
+
+    // Reveals the most frequent word of the body that is not part of the
+    // title and hasn't been guessed yet.
+    fn give_hint(&mut self) {
+        let Some(wiki_article) = &self.wiki_article else {
+            return;
+        };
+
+        let title_words = wiki_article.title_words();
+
+        let hint = wiki_article
+            .content_word_counts()
+            .into_iter()
+            .filter(|(word, _)| !self.guesses.contains(word) && !title_words.contains(word))
+            .max_by_key(|(_, count)| *count)
+            .map(|(word, _)| word);
+
+        match hint {
+            Some(word) => {
+                self.add_guess(word.clone());
+                self.announce(format!("Hint {}", self.hits_text(&word)));
+                self.selected_guess = word;
+                self.hints_used += 1;
+            }
+
+            None => {
+                self.toasts.info("There is nothing left to hint at");
+            }
+        }
+    }
+
+    // Word vector files are hundreds of megabytes, so they are loaded in the
+    // background and only while closeness is shown.
+    fn load_word_vectors(&mut self) {
+        let Some(path) = self.word_vectors_path.clone() else {
+            return;
+        };
+
+        self.word_vectors = None;
+        self.word_vectors_loading = Some(Task::spawn(&self.egui_ctx, String::from("Loading word vectors…"), move |_| {
+            WordVectors::load(&path)
+        }));
+    }
 
-    title_text_box: String,
-    toasts: Toasts,
-    next_guess: String,
-    selected_guess: String,
-    focus_on_guess: bool,
-}
+    fn poll_word_vectors(&mut self) {
+        let Some(loaded) = self.word_vectors_loading.as_ref().and_then(|task| task.poll()) else {
+            return;
+        };
 
-impl App {
-    fn load_article(&mut self) {
-        let downloaded = wikipedia_api::download_article(
-            self.selected_language.as_str(),
-            self.title_text_box.as_str());
+        self.word_vectors_loading = None;
 
-        match downloaded {
-            Ok((title, content)) => {
-                self.wiki_article = Some(article_parser::parse(title.as_str(), content.as_str()));
-                self.guesses.clear();
-                self.next_guess.clear();
-                self.title_text_box.clear();
-                self.selected_guess.clear();
+        match loaded {
+            Ok(word_vectors) => {
+                self.toasts.info(format!("Loaded {} word vectors", word_vectors.len()));
+                self.word_vectors = Some(word_vectors);
             }
 
             Err(e) => {
-                self.toasts.error(format!("{}", e));
+                self.toasts.error(format!("Could not load word vectors: {}", e));
             }
         }
     }
 
-    fn load_random_article(&mut self) {
-	let article = wikipedia_api::random_english_article();
+    // For every token whether a guess revealed it, and whether it is part of
+    // the selected guess. Phrase guesses only reveal their words where they
+    // occur together.
+    fn guessed_tokens(&self, tokens: &[Token]) -> Vec<(bool, bool)> {
+        let selected = self.selected_guess.to_lowercase();
+
+        let mut result: Vec<(bool, bool)> = tokens
+            .iter()
+            .map(|token| match token {
+                Token::Word(w, _) => {
+                    let word = w.to_lowercase();
+                    (self.guesses.contains(&word), word == selected)
+                }
+
+                Token::NonWord(..) => (false, false),
+            })
+            .collect();
 
-	match article {
-	    Ok(name) => {
-		self.selected_language = String::from("en");
-		self.title_text_box = name;
-		self.load_article();
-	    }
+        for guess in self.guesses.iter().filter(|guess| phrase::is_phrase(guess)) {
+            for range in phrase::find_phrase(guess, tokens) {
+                for i in range {
+                    result[i].0 = true;
+                    result[i].1 |= *guess == selected;
+                }
+            }
+        }
 
-	    Err(e) => {
-		self.toasts.error(format!("{}", e));
-	    }
-	}
+        result
     }
 
     fn title_complete(&self) -> bool {
         if let Some(wiki_article) = &self.wiki_article {
-            for token in &wiki_article.title {
-                if let Token::Word(w) = token {
-                    if !self.guesses.contains(&w.to_lowercase()) {
-                        return false;
-                    }
-                }
-            }
-
-            true
+            wiki_article.title
+                .iter()
+                .zip(self.guessed_tokens(&wiki_article.title))
+                .all(|(token, (revealed, _))| revealed || matches!(token, Token::NonWord(..)))
         } else {
             false
         }
     }
 
-    fn get_word(&self, word: &str) -> String {
-        if self.selected_guess.to_lowercase() == word.to_lowercase() {
-            String::from(word)
-        } else if self.guesses.contains(&word.to_lowercase()) || self.title_complete() {
+    fn get_word(word: &str, revealed: bool) -> String {
+        if revealed {
             String::from(word)
         } else {
             let dashes: Vec<&str> = std::iter::repeat("_").take(word.chars().count()).collect();
@@ -85,15 +1131,37 @@ impl App {
         }
     }
 
-    fn concat_tokens(&self, tokens: &Vec<Token>) -> String {
+    fn concat_tokens(&self, tokens: &[Token], reveal_all: bool) -> String {
         let mut result = String::new();
 
-        for token in tokens {
+        for (token, (revealed, _)) in tokens.iter().zip(self.guessed_tokens(tokens)) {
+            match token {
+                Token::Word(w, _) => {
+                    result.push_str(Self::get_word(w, revealed || reveal_all).as_str());
+                }
+                Token::NonWord(w, _) => {
+                    result.push_str(w);
+                }
+            }
+        }
+
+        result
+    }
+
+    // Like concat_tokens, but hidden words are described instead of drawn as
+    // underscores, which screen readers can't make sense of.
+    fn accessible_text(&self, tokens: &[Token], reveal_all: bool) -> String {
+        let mut result = String::new();
+
+        for (token, (revealed, _)) in tokens.iter().zip(self.guessed_tokens(tokens)) {
             match token {
-                Token::Word(w) => {
-                    result.push_str(self.get_word(w).as_str());
+                Token::Word(w, _) if !(revealed || reveal_all) => {
+                    match w.chars().count() {
+                        1 => result.push_str("hidden word, 1 letter"),
+                        letters => result.push_str(&format!("hidden word, {} letters", letters)),
+                    }
                 }
-                Token::NonWord(w) => {
+                Token::Word(w, _) | Token::NonWord(w, _) => {
                     result.push_str(w);
                 }
             }
@@ -102,22 +1170,407 @@ impl App {
         result
     }
 
+    // Replaces the name AccessKit reports for a widget showing redacted text.
+    fn describe_for_screen_reader(&self, response: &egui::Response, typ: egui::WidgetType, tokens: &[Token], reveal_all: bool) {
+        if self.accessible_mode {
+            let text = self.accessible_text(tokens, reveal_all);
+            response.widget_info(|| egui::WidgetInfo::labeled(typ, &text));
+        }
+    }
+
+    fn show_endpoint_editor(&mut self, ui: &mut egui::Ui) {
+        let mut removed = None;
+
+        for (i, endpoint) in self.endpoints.iter_mut().enumerate() {
+            ui.push_id(i, |ui| {
+                egui::Grid::new("endpoint_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Name:");
+                        ui.text_edit_singleline(&mut endpoint.name);
+                        ui.end_row();
+
+                        ui.label("API URL:");
+                        ui.text_edit_singleline(&mut endpoint.api_url);
+                        ui.end_row();
+
+                        ui.label("User agent:");
+                        let mut user_agent = endpoint.user_agent.clone().unwrap_or_default();
+                        if ui.text_edit_singleline(&mut user_agent).changed() {
+                            endpoint.user_agent = if user_agent.is_empty() { None } else { Some(user_agent) };
+                        }
+                        ui.end_row();
+
+                        let mut removed_header = None;
+                        for (j, (name, value)) in endpoint.headers.iter_mut().enumerate() {
+                            ui.label("Header:");
+                            ui.horizontal(|ui| {
+                                ui.add(TextEdit::singleline(name).desired_width(100.0));
+                                ui.text_edit_singleline(value);
+                                if ui.button("✖").clicked() {
+                                    removed_header = Some(j);
+                                }
+                            });
+                            ui.end_row();
+                        }
+
+                        if let Some(j) = removed_header {
+                            endpoint.headers.remove(j);
+                        }
+                    });
+
+                ui.horizontal(|ui| {
+                    if ui.button("add header").clicked() {
+                        endpoint.headers.push((String::new(), String::new()));
+                    }
+
+                    if ui.button("remove endpoint").clicked() {
+                        removed = Some(i);
+                    }
+                });
+            });
+
+            ui.separator();
+        }
+
+        if let Some(i) = removed {
+            if self.endpoints.len() > 1 {
+                self.endpoints.remove(i);
+                self.selected_endpoint = self.selected_endpoint.min(self.endpoints.len() - 1);
+            }
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("add endpoint").clicked() {
+                self.endpoints.push(WikiEndpoint::new("New wiki", "https://example.org/w/api.php"));
+            }
+
+            if ui.button("save").clicked() {
+                if let Err(e) = wiki_endpoint::save_endpoints(&self.endpoints) {
+                    self.toasts.error(format!("{}", e));
+                }
+            }
+        });
+    }
+
+    fn show_language_picker(&mut self, ui: &mut egui::Ui) {
+        let selected_text = self.languages
+            .iter()
+            .find(|language| language.code == self.selected_language)
+            .map(|language| language.label())
+            .unwrap_or(self.selected_language.clone());
+
+        // The list is searched in a window of its own, a text field in a
+        // combo box popup would close the popup when clicked.
+        if ui.button(format!("{} ⏷", selected_text)).clicked() {
+            self.show_language_window = !self.show_language_window;
+        }
+
+        if self.languages_refresh.is_some() {
+            ui.spinner();
+        } else if ui.button("↻").on_hover_text("Refresh the list of languages").clicked() {
+            self.languages_refresh = Some(Task::spawn(
+                &self.egui_ctx,
+                String::from("Refreshing languages…"),
+                |_| languages::refresh_languages()));
+        }
+    }
+
+    fn show_language_list(&mut self, ui: &mut egui::Ui) {
+        let filter = ui.add(TextEdit::singleline(&mut self.language_filter).hint_text("search"));
+
+        if ui.memory(|mem| mem.focus().is_none()) {
+            filter.request_focus();
+        }
+
+        let mut chosen = None;
+
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            for language in &self.languages {
+                if !language.matches(&self.language_filter) {
+                    continue;
+                }
+
+                let is_selected = language.code == self.selected_language;
+                if ui.selectable_label(is_selected, language.label()).clicked() {
+                    chosen = Some(language.code.clone());
+                }
+            }
+        });
+
+        if let Some(code) = chosen {
+            self.selected_language = code.clone();
+            self.default_language = code;
+            self.language_filter.clear();
+            self.show_language_window = false;
+        }
+    }
+
+    fn poll_languages_refresh(&mut self) {
+        let Some(result) = self.languages_refresh.as_ref().and_then(|task| task.poll()) else {
+            return;
+        };
+
+        self.languages_refresh = None;
+
+        match result {
+            Ok(languages) => {
+                self.languages = languages;
+                self.toasts.success("Language list updated");
+            }
+
+            Err(e) => {
+                self.report_fetch_error(e);
+            }
+        }
+    }
+
+    fn show_tokenizer_options(&mut self, ui: &mut egui::Ui) {
+        ui.label("Applies to the next article loaded.");
+
+        ui.add_enabled(
+            cfg!(feature = "dictionary-segmentation"),
+            Checkbox::new(&mut self.tokenizer_options.dictionary_segmentation, "Dictionary segmentation for Chinese, Thai, Lao, Khmer and Myanmar"));
+
+        ui.checkbox(&mut self.tokenizer_options.join_apostrophes, "Keep contractions like \"don't\" together");
+        ui.checkbox(&mut self.tokenizer_options.join_hyphens, "Keep hyphenated words like \"well-known\" together");
+
+        ui.separator();
+        ui.label("Numbers:");
+        ui.radio_value(&mut self.tokenizer_options.numbers, NumberRule::Guessable, "must be guessed");
+        ui.radio_value(&mut self.tokenizer_options.numbers, NumberRule::Revealed, "are revealed");
+    }
+
+    fn show_pack_bar(&mut self, ui: &mut egui::Ui) {
+        let Some(pack) = &self.pack else {
+            return;
+        };
+
+        let mut play = None;
+        let mut close = false;
+
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "Pack: {} ({}/{} solved)",
+                pack.title,
+                self.pack_progress.solved_count(pack),
+                pack.entries.len()));
+
+            // Titles of unsolved entries would give the answer away.
+            let entry_label = |i: usize| {
+                if self.pack_progress.is_solved(pack, i) {
+                    format!("✔ {}. {}", i + 1, pack.entries[i].title)
+                } else {
+                    format!("{}. ???", i + 1)
+                }
+            };
+
+            let selected_text = self.pack_entry
+                .map(entry_label)
+                .unwrap_or(String::from("choose an entry"));
+
+            egui::ComboBox::from_id_source("pack_entry_combo")
+                .selected_text(selected_text)
+                .show_ui(ui, |ui| {
+                    for i in 0..pack.entries.len() {
+                        if ui.selectable_label(self.pack_entry == Some(i), entry_label(i)).clicked() {
+                            play = Some(i);
+                        }
+                    }
+                });
+
+            let current = self.pack_entry.unwrap_or(0);
+
+            if ui.add_enabled(current > 0, Button::new("previous")).clicked() {
+                play = Some(current - 1);
+            }
+
+            if ui.add_enabled(current + 1 < pack.entries.len(), Button::new("next")).clicked() {
+                play = Some(current + 1);
+            }
+
+            if ui.button("close pack").clicked() {
+                close = true;
+            }
+        });
+
+        if let Some(i) = play {
+            self.play_pack_entry(i);
+        }
+
+        if close {
+            self.pack = None;
+            self.pack_entry = None;
+        }
+    }
+
+    fn show_pack_editor(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("pack_creator_grid")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Pack title:");
+                ui.text_edit_singleline(&mut self.pack_creator.title);
+                ui.end_row();
+
+                ui.label("Articles (one per line):");
+                ui.text_edit_multiline(&mut self.pack_creator.titles);
+                ui.end_row();
+            });
+
+        ui.checkbox(&mut self.pack_creator.include_articles, "Store parsed articles in the pack");
+        ui.label(format!(
+            "Articles are downloaded from {} in language \"{}\".",
+            self.endpoints[self.selected_endpoint].name,
+            self.selected_language));
+
+        if let Some(task) = &self.pack_creator.creating {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label(task.message());
+            });
+        } else if ui.button("create and save…").clicked() {
+            self.create_pack();
+        }
+    }
+
+    fn show_difficulty_options(&mut self, ui: &mut egui::Ui) {
+        ui.label("Difficulty of random articles:");
+
+        let (min, max) = &mut self.random_difficulty;
+        ui.add(Slider::new(min, difficulty::MIN_SCORE..=difficulty::MAX_SCORE).text("min"));
+        ui.add(Slider::new(max, difficulty::MIN_SCORE..=difficulty::MAX_SCORE).text("max"));
+
+        if *min > *max {
+            *max = *min;
+        }
+    }
+
+    fn show_word_list_options(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.require_known_words, "Only accept words from the word list");
+
+        let path_label = self.word_list_paths
+            .get(&self.selected_language)
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or(String::from("none"));
+        let size_label = match &self.word_list {
+            Some((language, word_list)) if *language == self.selected_language => format!(" ({} words)", word_list.len()),
+            _ => String::from(""),
+        };
+        ui.label(format!("Word list for \"{}\": {}{}", self.selected_language, path_label, size_label));
+
+        ui.horizontal(|ui| {
+            if ui.button("Choose word list…").clicked() {
+                let path = rfd::FileDialog::new()
+                    .add_filter("Word lists", &["txt", "dic"])
+                    .pick_file();
+
+                if let Some(path) = path {
+                    self.word_list_paths.insert(self.selected_language.clone(), path);
+                    self.word_list = None;
+                    self.require_known_words = true;
+                    self.load_word_list();
+                }
+            }
+
+            if self.word_list_paths.contains_key(&self.selected_language) && ui.button("Remove").clicked() {
+                self.word_list_paths.remove(&self.selected_language);
+                self.word_list = None;
+            }
+        });
+    }
+
+    fn show_closeness_options(&mut self, ui: &mut egui::Ui) {
+        if ui.checkbox(&mut self.show_closeness, "Show semantic closeness of misses").changed() {
+            if !self.show_closeness {
+                self.word_vectors = None;
+                self.word_vectors_loading = None;
+            } else if self.word_vectors.is_none() && self.word_vectors_loading.is_none() {
+                self.load_word_vectors();
+            }
+        }
+
+        let path_label = self.word_vectors_path
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or(String::from("none"));
+
+        ui.horizontal(|ui| {
+            ui.label(format!("Word vectors: {}", path_label));
+
+            if self.word_vectors_loading.is_some() {
+                ui.spinner();
+            }
+        });
+
+        if ui.button("Choose word vectors (.vec)…").clicked() {
+            let path = rfd::FileDialog::new()
+                .add_filter("Word vectors", &["vec", "txt"])
+                .pick_file();
+
+            if path.is_some() {
+                self.word_vectors_path = path;
+                self.show_closeness = true;
+                self.load_word_vectors();
+            }
+        }
+    }
+
     fn show_top_bar(&mut self, ui: &mut egui::Ui) {
             ui.horizontal(|ui| {
-                ui.label("Language code:");
+                ui.label("Wiki:");
+
+                egui::ComboBox::from_id_source("endpoint_combo")
+                    .selected_text(self.endpoints[self.selected_endpoint].name.as_str())
+                    .show_ui(ui, |ui| {
+                        for (i, endpoint) in self.endpoints.iter().enumerate() {
+                            ui.selectable_value(&mut self.selected_endpoint, i, endpoint.name.as_str());
+                        }
+                    });
 
-                let language_code = TextEdit::singleline(&mut self.selected_language)
-                    .desired_width(30.0);
-                ui.add(language_code);
+                if ui.button("⚙").clicked() {
+                    self.show_endpoint_settings = !self.show_endpoint_settings;
+                }
+
+                if self.endpoints[self.selected_endpoint].uses_language() {
+                    ui.label("Language:");
+                    self.show_language_picker(ui);
+                }
 
                 ui.label("Article:");
 
                 let title_text_box = TextEdit::singleline(&mut self.title_text_box);
                 let resp = ui.add(title_text_box);
+                let suggestions_popup = ui.make_persistent_id("title_suggestions");
+
+                if resp.changed() {
+                    self.title_search.edited(&self.title_text_box);
+                    ui.memory_mut(|mem| mem.open_popup(suggestions_popup));
+                }
+
                 if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
                     self.load_article();
                 }
 
+                let mut chosen = None;
+
+                if !self.title_search.suggestions.is_empty() {
+                    egui::popup_below_widget(ui, suggestions_popup, &resp, |ui| {
+                        for suggestion in &self.title_search.suggestions {
+                            if ui.selectable_label(false, suggestion.as_str()).clicked() {
+                                chosen = Some(suggestion.clone());
+                            }
+                        }
+                    });
+                }
+
+                if let Some(title) = chosen {
+                    ui.memory_mut(|mem| mem.close_popup());
+                    self.title_text_box = title;
+                    self.load_article();
+                }
+
                 let load_btn = ui.button("load");
 
                 if load_btn.clicked() {
@@ -129,35 +1582,169 @@ impl App {
 		if random_btn.clicked() {
 		    self.load_random_article();
 		}
+
+                if let Some(loading) = &self.loading {
+                    ui.spinner();
+                    ui.label(loading.task.message());
+
+                    if ui.small_button("✖").on_hover_text("Cancel").clicked() {
+                        self.loading = None;
+                    }
+                }
+
+                let open_file_btn = ui.button("Open file…");
+
+                if open_file_btn.clicked() {
+                    self.open_file();
+                }
+
+                let save_btn = ui.add_enabled(self.wiki_article.is_some(), Button::new("Save article…"));
+
+                if save_btn.clicked() {
+                    self.save_article();
+                }
+
+                ui.menu_button("Packs", |ui| {
+                    if ui.button("Open pack…").clicked() {
+                        ui.close_menu();
+                        self.open_pack();
+                    }
+
+                    if ui.button("Create pack…").clicked() {
+                        ui.close_menu();
+                        self.show_pack_creator = true;
+                    }
+                });
+
+                ui.menu_button("Options", |ui| {
+                    self.show_tokenizer_options(ui);
+
+                    ui.separator();
+                    self.show_difficulty_options(ui);
+
+                    ui.separator();
+                    ui.checkbox(&mut self.show_toc, "Show table of contents");
+                    ui.checkbox(&mut self.underline_links, "Underline links");
+                    ui.checkbox(&mut self.progressive_reveal, "Reveal sections progressively");
+                    ui.add_enabled(
+                        self.progressive_reveal,
+                        Slider::new(&mut self.guesses_per_unlock, 1..=50).text("guesses per section"));
+
+                    ui.separator();
+                    ui.checkbox(&mut self.phrase_guesses, "Allow phrase guesses")
+                        .on_hover_text("Words in quotes, like \"new york\", are only revealed where they occur together");
+                    ui.checkbox(&mut self.check_typos, "Warn about likely typos")
+                        .on_hover_text("Asks for confirmation of guesses that are one letter away from an earlier guess, or that are not in the word vectors' vocabulary");
+
+                    ui.separator();
+                    self.show_word_list_options(ui);
+
+                    ui.separator();
+                    self.show_closeness_options(ui);
+                });
+
+                if ui.button("Statistics").clicked() {
+                    self.show_statistics = true;
+                }
+
+                if ui.button("Settings").clicked() {
+                    self.show_settings = true;
+                }
+
+                if ui.button("⌨").on_hover_text("Keyboard shortcuts").clicked() {
+                    self.show_shortcuts = true;
+                }
             });
     }
 
-    fn show_title(&self, ui: &mut egui::Ui, tokens: &Vec<Token>) {
-        let text = self.concat_tokens(&tokens);
-        ui.label(egui::RichText::new(text).heading().monospace());
+    fn tokens_text(tokens: &Vec<Token>) -> String {
+        let mut result = String::new();
+
+        for token in tokens {
+            match token {
+                Token::Word(w, _) | Token::NonWord(w, _) => {
+                    result.push_str(w);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn heading_display_text(&self, tokens: &Vec<Token>, reveal_all: bool) -> String {
+        let text = self.concat_tokens(tokens, reveal_all);
+
+        if bidi_layout::is_rtl(&Self::tokens_text(tokens)) {
+            bidi_layout::visual_line(&text)
+        } else {
+            text
+        }
+    }
+
+    fn show_heading_text(&self, ui: &mut egui::Ui, tokens: &Vec<Token>, reveal_all: bool) {
+        let text = self.heading_display_text(tokens, reveal_all);
+
+        let response = if bidi_layout::is_rtl(&Self::tokens_text(tokens)) {
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::TOP), |ui| {
+                ui.label(egui::RichText::new(text).font(self.appearance.heading_font_id()))
+            }).inner
+        } else {
+            ui.label(egui::RichText::new(text).font(self.appearance.heading_font_id()))
+        };
+
+        self.describe_for_screen_reader(&response, egui::WidgetType::Label, tokens, reveal_all);
+    }
+
+    fn show_title(&self, ui: &mut egui::Ui, tokens: &Vec<Token>, reveal_all: bool) {
+        self.show_heading_text(ui, tokens, reveal_all);
         ui.add_space(30.0);
     }
 
-    fn add_word(&self, job: &mut LayoutJob, ui: &egui::Ui, word: &str) {
-        if self.selected_guess.to_lowercase() == word.to_lowercase() {
+    // Bold and italics are only shown once a word is revealed, but whether a
+    // word is part of a link is visible from the start if enabled, and always
+    // once the game is won.
+    fn text_format(&self, ui: &egui::Ui, style: &Style, revealed: bool, reveal_all: bool) -> TextFormat {
+        let mut format = TextFormat {
+            font_id: self.appearance.article_font_id(),
+            ..Default::default()
+        };
+
+        if revealed {
+            if style.bold {
+                format.color = ui.visuals().strong_text_color();
+            }
+            format.italics = style.italic;
+        }
+
+        if (self.underline_links || reveal_all) && style.link.is_some() {
+            format.underline = Stroke::new(1.0, ui.visuals().hyperlink_color);
+        }
+
+        format
+    }
+
+    fn highlighted(&self, format: TextFormat) -> TextFormat {
+        TextFormat {
+            color: self.appearance.highlight_text,
+            background: self.appearance.highlight_background,
+            ..format
+        }
+    }
+
+    // format is the text format of the word, revealed or not. Highlighted
+    // words are always revealed.
+    fn add_word(&self, job: &mut LayoutJob, word: &str, format: TextFormat, revealed: bool, highlighted: bool) {
+        if highlighted {
             job.append(
                 word,
                 0.0,
-                TextFormat {
-                    font_id: egui::TextStyle::Monospace.resolve(ui.style()),
-                    color: Color32::BLACK,
-                    background: Color32::LIGHT_BLUE,
-                    ..Default::default()
-                },
+                self.highlighted(format),
             );
-        } else if self.guesses.contains(&word.to_lowercase()) || self.title_complete() {
+        } else if revealed {
             job.append(
                 word,
                 0.0,
-                TextFormat {
-                    font_id: egui::TextStyle::Monospace.resolve(ui.style()),
-                    ..Default::default()
-                },
+                format,
             );
         } else {
             let dashes: Vec<&str> = std::iter::repeat("_").take(word.chars().count()).collect();
@@ -165,54 +1752,148 @@ impl App {
             job.append(
                 &dashes,
                 0.0,
-                TextFormat {
-                    font_id: egui::TextStyle::Monospace.resolve(ui.style()),
-                    ..Default::default()
-                },
+                format,
             );
         }
     }
 
-    fn show_paragraph(&self, ui:  &mut egui::Ui, tokens: &Vec<Token>) {
+    // The link target of the token containing the given character.
+    fn link_at(tokens: &Vec<Token>, index: usize) -> Option<&String> {
+        let mut start = 0;
+
+        for token in tokens {
+            let (Token::Word(text, style) | Token::NonWord(text, style)) = token;
+            let end = start + text.chars().count();
+
+            if index >= start && index < end {
+                return style.link.as_ref();
+            }
+
+            start = end;
+        }
+
+        None
+    }
+
+    fn show_paragraph(&self, ui:  &mut egui::Ui, tokens: &Vec<Token>, actions: &mut ArticleActions) {
         let mut job = LayoutJob::default();
         job.wrap.max_width = ui.available_width();
 
-        for token in tokens {
+        let reveal_all = actions.reveal_all;
+
+        // Character offset of the occurrence to scroll to, if it is in this
+        // paragraph
+        let mut jump_offset = None;
+        let mut offset = 0;
+        let mut previous_highlighted = false;
+
+        for (token, (revealed, highlighted)) in tokens.iter().zip(self.guessed_tokens(tokens)) {
             match token {
-                Token::Word(w) => {
-                    self.add_word(&mut job, &ui, w);
+                Token::Word(w, style) => {
+                    // A selected phrase counts once, not once per word
+                    if highlighted && !previous_highlighted {
+                        if self.jump_to_occurrence == Some(actions.occurrences) {
+                            jump_offset = Some(offset);
+                        }
+                        actions.occurrences += 1;
+                    }
+
+                    let revealed = revealed || reveal_all;
+                    let format = self.text_format(ui, style, revealed || highlighted, reveal_all);
+                    self.add_word(&mut job, w, format, revealed, highlighted);
+                    offset += w.chars().count();
+                }
+                Token::NonWord(w, style) => {
+                    // Only the separators inside a selected phrase are highlighted
+                    let format = if highlighted {
+                        self.highlighted(self.text_format(ui, style, true, reveal_all))
+                    } else {
+                        self.text_format(ui, style, true, reveal_all)
+                    };
+                    job.append(w, 0.0, format);
+                    offset += w.chars().count();
                 }
-                Token::NonWord(w) => {
-                    job.append(
-                        w,
-                        0.0,
-                        TextFormat {
-                            font_id: egui::TextStyle::Monospace.resolve(ui.style()),
-                            ..Default::default()
-                        },
-                    );
+            }
+
+            previous_highlighted = highlighted;
+        }
+
+        if bidi_layout::is_rtl(&Self::tokens_text(tokens)) {
+            let rows = bidi_layout::visual_rows(ui, job);
+
+            let response = ui.with_layout(egui::Layout::top_down(egui::Align::Max), |ui| {
+                for (i, row) in rows.into_iter().enumerate() {
+                    let galley = ui.fonts(|fonts| {
+                        fonts.layout_job(row)
+                    });
+                    let row_response = ui.label(galley);
+
+                    // The first row is read as the whole paragraph
+                    if i == 0 {
+                        self.describe_for_screen_reader(&row_response, egui::WidgetType::Label, tokens, reveal_all);
+                    } else if self.accessible_mode {
+                        row_response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Label, ""));
+                    }
                 }
+            }).response;
+
+            // The rows are reordered, so only the paragraph is scrolled to
+            if jump_offset.is_some() {
+                ui.scroll_to_rect(response.rect, Some(egui::Align::Center));
             }
+            return;
         }
 
         let galley = ui.fonts(|fonts| {
             fonts.layout_job(job)
         });
-        ui.label(galley);
+
+        // Once the game is won, links start a new game on the linked article.
+        let response = if reveal_all {
+            ui.add(Label::new(galley.clone()).sense(egui::Sense::click()))
+        } else {
+            ui.label(galley.clone())
+        };
+
+        self.describe_for_screen_reader(&response, egui::WidgetType::Label, tokens, reveal_all);
+
+        if let Some(offset) = jump_offset {
+            let cursor = galley.from_ccursor(egui::text::CCursor::new(offset));
+            let rect = galley.pos_from_cursor(&cursor).translate(response.rect.min.to_vec2());
+            ui.scroll_to_rect(rect, Some(egui::Align::Center));
+        }
+
+        if !reveal_all {
+            return;
+        }
+
+        let Some(pos) = response.hover_pos() else {
+            return;
+        };
+
+        let cursor = galley.cursor_from_pos(pos - response.rect.min);
+        if let Some(target) = Self::link_at(tokens, cursor.ccursor.index) {
+            ui.output_mut(|output| output.cursor_icon = egui::CursorIcon::PointingHand);
+
+            if response.clicked() {
+                actions.follow_link = Some(target.clone());
+            }
+
+            response.on_hover_text(target);
+        }
     }
 
-    fn show_sections(&self, ui: &mut egui::Ui, sections: &Vec<Section>) {
+    fn show_sections(&self, ui: &mut egui::Ui, sections: &Vec<Section>, actions: &mut ArticleActions) {
         for section in sections {
             match section {
                 Section::Heading(_level, tokens) => {
-                    let text = self.concat_tokens(&tokens);
                     ui.add_space(30.0);
-                    ui.label(egui::RichText::new(text).heading().monospace());
+                    self.show_heading_text(ui, tokens, actions.reveal_all);
                     ui.add_space(10.0);
                 }
 
                 Section::Paragraph(tokens) => {
-                    self.show_paragraph(ui, tokens);
+                    self.show_paragraph(ui, tokens, actions);
                     ui.add_space(10.0);
 
                     // let text = self.concat_tokens(&tokens);
@@ -226,7 +1907,7 @@ impl App {
                             ui.label("•");
 
                             ui.vertical(|ui| {
-                                self.show_sections(ui, item);
+                                self.show_sections(ui, item, actions);
                             });
                         });
                     }
@@ -238,7 +1919,7 @@ impl App {
                             ui.label(format!("{}.", i + 1));
 
                             ui.vertical(|ui| {
-                                self.show_sections(ui, item);
+                                self.show_sections(ui, item, actions);
                             });
                         });
                     }
@@ -247,21 +1928,153 @@ impl App {
         }
     }
 
-    fn show_article(&self, ui: &mut egui::Ui) {
+    fn unlocked_chapters(&self) -> usize {
+        if !self.progressive_reveal || self.title_complete() {
+            return usize::MAX;
+        }
+
+        self.extra_unlocks + self.guesses.len() / self.guesses_per_unlock.max(1)
+    }
+
+    // index is the position of the next chapter in reading order.
+    fn show_chapters(&self, ui: &mut egui::Ui, chapters: &Vec<Chapter>, index: &mut usize, unlocked: usize, actions: &mut ArticleActions) {
+        for chapter in chapters {
+            let position = *index;
+
+            if position >= unlocked {
+                return;
+            }
+
+            let id = ui.make_persistent_id(("chapter", self.article_generation, position));
+            let mut state = egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, true);
+
+            // Open the chapter if the scroll target is the chapter itself or
+            // one of its subchapters.
+            let target = self.scroll_to_chapter
+                .filter(|target| *target >= position && *target < position + chapter.count());
+            if target.is_some() {
+                state.set_open(true);
+            }
+
+            *index += 1;
+
+            ui.add_space(30.0);
+            let (_, header, _) = state
+                .show_header(ui, |ui| {
+                    self.show_heading_text(ui, &chapter.heading, actions.reveal_all);
+                })
+                .body_unindented(|ui| {
+                    ui.add_space(10.0);
+                    self.show_sections(ui, &chapter.content, actions);
+                    self.show_chapters(ui, &chapter.subchapters, index, unlocked, actions);
+                });
+
+            if target == Some(position) {
+                header.response.scroll_to_me(Some(egui::Align::TOP));
+                actions.reached_scroll_target = true;
+            }
+
+            // Collapsed subchapters are skipped.
+            *index = position + chapter.count();
+        }
+    }
+
+    fn show_article(&self, ui: &mut egui::Ui) -> ArticleActions {
+        let mut actions = ArticleActions {
+            reveal_all: self.title_complete(),
+            unlock_next: false,
+            reached_scroll_target: false,
+            follow_link: None,
+            occurrences: 0,
+        };
+
         if let Some(wiki_article) = &self.wiki_article {
             egui::ScrollArea::vertical().show(ui, |ui| {
-                self.show_title(ui, &wiki_article.title);
+                self.show_title(ui, &wiki_article.title, actions.reveal_all);
+
+                self.show_sections(ui, &wiki_article.lead, &mut actions);
+
+                let unlocked = self.unlocked_chapters();
+                let mut index = 0;
+                self.show_chapters(ui, &wiki_article.chapters, &mut index, unlocked, &mut actions);
 
-                self.show_sections(ui, &wiki_article.content);
+                let locked = wiki_article.chapter_count().saturating_sub(unlocked);
+                if locked > 0 {
+                    ui.add_space(30.0);
+                    ui.label(format!("🔒 {} more sections, one is unlocked every {} guesses", locked, self.guesses_per_unlock));
+
+                    if ui.button("unlock next section").clicked() {
+                        actions.unlock_next = true;
+                    }
+                }
             });
         }
+
+        actions
+    }
+
+    fn show_toc_entries(&self, ui: &mut egui::Ui, chapters: &Vec<Chapter>, index: &mut usize, unlocked: usize, reveal_all: bool, clicked: &mut Option<usize>) {
+        for chapter in chapters {
+            let position = *index;
+
+            if position >= unlocked {
+                let response = ui.label("🔒");
+                if self.accessible_mode {
+                    response.widget_info(|| egui::WidgetInfo::labeled(egui::WidgetType::Label, "locked chapters"));
+                }
+                return;
+            }
+
+            let text = self.heading_display_text(&chapter.heading, reveal_all);
+            let response = ui.selectable_label(false, egui::RichText::new(text).font(self.appearance.article_font_id()));
+            self.describe_for_screen_reader(&response, egui::WidgetType::SelectableLabel, &chapter.heading, reveal_all);
+
+            if response.clicked() {
+                *clicked = Some(position);
+            }
+
+            *index += 1;
+
+            if !chapter.subchapters.is_empty() {
+                ui.indent(("toc", position), |ui| {
+                    self.show_toc_entries(ui, &chapter.subchapters, index, unlocked, reveal_all, clicked);
+                });
+            }
+
+            *index = position + chapter.count();
+        }
+    }
+
+    // Returns the chapter to jump to.
+    fn show_table_of_contents(&self, ui: &mut egui::Ui) -> Option<usize> {
+        let Some(wiki_article) = &self.wiki_article else {
+            return None;
+        };
+
+        let mut clicked = None;
+        let reveal_all = self.title_complete();
+
+        ui.heading("Contents");
+
+        egui::ScrollArea::vertical()
+            .id_source("toc_scroll")
+            .show(ui, |ui| {
+                let mut index = 0;
+                self.show_toc_entries(ui, &wiki_article.chapters, &mut index, self.unlocked_chapters(), reveal_all, &mut clicked);
+            });
+
+        clicked
     }
 
     fn count_word_in_tokens(word: &str, tokens: &Vec<Token>) -> usize {
+        if phrase::is_phrase(word) {
+            return phrase::find_phrase(word, tokens).len();
+        }
+
         let mut result = 0;
 
         for token in tokens {
-            if let Token::Word(w) = token {
+            if let Token::Word(w, _) = token {
                 if w.to_lowercase() == word.to_lowercase() {
                     result += 1;
                 }
@@ -299,20 +2112,35 @@ impl App {
         result
     }
 
+    fn count_word_in_chapters(word: &str, chapters: &Vec<Chapter>) -> usize {
+        let mut result = 0;
+
+        for chapter in chapters {
+            result += Self::count_word_in_tokens(word, &chapter.heading);
+            result += Self::count_word_in_sections(word, &chapter.content);
+            result += Self::count_word_in_chapters(word, &chapter.subchapters);
+        }
+        result
+    }
+
     fn count_word_in_article(&self, word: &str) -> usize {
         let Some(wiki_article) = &self.wiki_article else {
             panic!("count_word_in_article called without article present");
         };
 
         Self::count_word_in_tokens(word, &wiki_article.title)
-            + Self::count_word_in_sections(word, &wiki_article.content)
+            + Self::count_word_in_sections(word, &wiki_article.lead)
+            + Self::count_word_in_chapters(word, &wiki_article.chapters)
 
     }
 
     fn show_guesses(&mut self, ui: &mut egui::Ui) {
+        if let Some(score) = self.current_difficulty {
+            ui.label(format!("Difficulty: {} ({})", score, difficulty::label(score)));
+        }
 
         let next_guess_edit = TextEdit::singleline(&mut self.next_guess);
-        let resp = ui.add(next_guess_edit);
+        let resp = ui.add_enabled(self.replay.is_none(), next_guess_edit);
 
         if self.focus_on_guess {
             resp.request_focus();
@@ -320,16 +2148,64 @@ impl App {
         }
 
         if resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
-            if !self.next_guess.is_empty() {
-                self.guesses.insert(self.next_guess.trim().to_lowercase());
-                self.next_guess.clear();
-            }
+            self.submit_guess();
             self.focus_on_guess = true;
         }
 
+        let hints_left = self.allowed_hints().map(|allowed| allowed.saturating_sub(self.hints_used));
+        let hint_label = match hints_left {
+            Some(left) => format!("hint ({} left)", left),
+            None => String::from("hint"),
+        };
+
+        let can_hint = hints_left != Some(0) && !self.title_complete() && self.replay.is_none();
+        let can_undo = !self.guess_log.is_empty() && self.replay.is_none();
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(can_hint, Button::new(hint_label)).clicked() {
+                self.give_hint();
+            }
+
+            let undo_label = match self.guess_log.last() {
+                Some(last) => format!("Remove \"{}\"", last),
+                None => String::from("Nothing to undo"),
+            };
+            if ui.add_enabled(can_undo, Button::new("undo")).on_hover_text(undo_label).clicked() {
+                self.undo_guess();
+            }
+        });
+
+        if self.accessible_mode && !self.announcement.is_empty() {
+            let response = ui.label(&self.announcement);
+            ui.ctx().accesskit_node_builder(response.id, |builder| {
+                builder.set_live(egui::accesskit::Live::Polite);
+            });
+        }
+
+        if !self.selected_guess.is_empty() && self.occurrence_count > 0 {
+            let current = self.current_occurrence
+                .map(|i| format!("{} of ", i % self.occurrence_count + 1))
+                .unwrap_or_default();
+
+            ui.label(format!("{}{} shown occurrences", current, self.occurrence_count))
+                .on_hover_text(format!(
+                    "{} and {} jump between them",
+                    ui.ctx().format_shortcut(&NEXT_OCCURRENCE_SHORTCUT),
+                    ui.ctx().format_shortcut(&PREVIOUS_OCCURRENCE_SHORTCUT)));
+        }
+
+        let title_words = self.wiki_article
+            .as_ref()
+            .map(|wiki_article| wiki_article.title_words())
+            .unwrap_or_default();
+
+        let show_closeness = self.show_closeness && self.word_vectors.is_some();
+
+        let mut removed = None;
+
         egui::ScrollArea::vertical().stick_to_bottom(true).show(ui, |ui| {
             egui::Grid::new("guesses_grid")
-                .num_columns(2)
+                .num_columns(if show_closeness { 3 } else { 2 })
                 .striped(true)
                 .show(ui, |ui| {
                     for guess in &self.guesses {
@@ -337,27 +2213,414 @@ impl App {
 
                         ui.label(format!("{}", occurs));
 
+                        if let (true, Some(word_vectors)) = (show_closeness, &self.word_vectors) {
+                            if occurs > 0 {
+                                ui.label("");
+                            } else {
+                                let closeness = word_vectors
+                                    .closest_similarity(guess, &title_words)
+                                    .map(|similarity| format!("{:.0}%", similarity.max(0.0) * 100.0))
+                                    .unwrap_or(String::from("?"));
+
+                                ui.label(closeness)
+                                    .on_hover_text("Similarity to the closest word of the title");
+                            }
+                        }
+
                         let is_guess_selected = *guess == self.selected_guess;
-                        if ui.selectable_label(is_guess_selected, guess).clicked() {
+                        let label = ui.selectable_label(is_guess_selected, guess);
+                        if label.clicked() {
                             if is_guess_selected {
                                 self.selected_guess = String::from("");
                             } else {
                                 self.selected_guess = guess.clone();
                             }
+                            self.current_occurrence = None;
+                        }
+
+                        if is_guess_selected && self.scroll_to_selected_guess {
+                            label.scroll_to_me(None);
+                        }
+
+                        if self.replay.is_none() {
+                            label.context_menu(|ui| {
+                                if ui.button("Remove guess").clicked() {
+                                    removed = Some(guess.clone());
+                                    ui.close_menu();
+                                }
+                            });
                         }
                         ui.end_row();
                     }
                 });
         });
+
+        self.scroll_to_selected_guess = false;
+
+        if let Some(guess) = removed {
+            self.remove_guess(&guess);
+        }
+    }
+
+    fn show_settings_window(&mut self, ui: &mut egui::Ui) {
+        let before = self.appearance.clone();
+
+        egui::Grid::new("settings_grid").num_columns(2).show(ui, |ui| {
+            ui.label("Zoom");
+            ui.add(Slider::new(&mut self.appearance.zoom, appearance::MIN_ZOOM..=appearance::MAX_ZOOM).step_by(0.25));
+            ui.end_row();
+
+            ui.label("Theme");
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.appearance.theme, Theme::Light, "Light");
+                ui.selectable_value(&mut self.appearance.theme, Theme::Dark, "Dark");
+            });
+            ui.end_row();
+
+            ui.label("Article font");
+            ui.horizontal(|ui| {
+                ui.selectable_value(&mut self.appearance.article_font, ArticleFont::Monospace, "Monospace");
+                ui.selectable_value(&mut self.appearance.article_font, ArticleFont::Proportional, "Proportional");
+            });
+            ui.end_row();
+
+            ui.label("Font size");
+            ui.add(Slider::new(&mut self.appearance.font_size, appearance::MIN_FONT_SIZE..=appearance::MAX_FONT_SIZE).step_by(1.0));
+            ui.end_row();
+
+            ui.label("Highlight");
+            ui.horizontal(|ui| {
+                ui.color_edit_button_srgba(&mut self.appearance.highlight_text).on_hover_text("Text");
+                ui.color_edit_button_srgba(&mut self.appearance.highlight_background).on_hover_text("Background");
+
+                let preview = TextFormat {
+                    color: self.appearance.highlight_text,
+                    background: self.appearance.highlight_background,
+                    font_id: self.appearance.article_font_id(),
+                    ..Default::default()
+                };
+                ui.label(LayoutJob::single_section(String::from("example"), preview));
+            });
+            ui.end_row();
+
+            ui.label("Presets");
+            egui::ComboBox::from_id_source("highlight_presets")
+                .selected_text("Choose…")
+                .show_ui(ui, |ui| {
+                    for (name, text, background) in appearance::highlight_presets() {
+                        if ui.selectable_label(false, name).clicked() {
+                            self.appearance.highlight_text = text;
+                            self.appearance.highlight_background = background;
+                        }
+                    }
+                });
+            ui.end_row();
+        });
+
+        if ui.button("Reset to defaults").clicked() {
+            self.appearance = Appearance::default();
+        }
+
+        if self.appearance != before {
+            self.appearance.apply(ui.ctx());
+        }
+
+        ui.separator();
+        ui.checkbox(&mut self.accessible_mode, "Screen reader mode")
+            .on_hover_text("Hidden words are read as \"hidden word, N letters\" and the hits of every guess are announced");
+    }
+
+    fn show_statistics_window(&mut self, ui: &mut egui::Ui) {
+        let statistics = history::statistics(&self.history);
+
+        egui::Grid::new("statistics_grid").num_columns(2).show(ui, |ui| {
+            ui.label("Games played");
+            ui.label(format!("{}", statistics.played));
+            ui.end_row();
+
+            ui.label("Solved");
+            ui.label(format!("{}", statistics.solved));
+            ui.end_row();
+
+            ui.label("Average guesses");
+            ui.label(format!("{:.1}", statistics.average_guesses));
+            ui.end_row();
+
+            ui.label("Average accuracy");
+            ui.label(format!("{:.0}%", statistics.average_accuracy * 100.0));
+            ui.end_row();
+
+            ui.label("Average time");
+            ui.label(history::format_duration(statistics.average_duration_secs as u64));
+            ui.end_row();
+
+            ui.label("Average hints");
+            ui.label(format!("{:.1}", statistics.average_hints));
+            ui.end_row();
+
+            ui.label("Streak");
+            ui.label(format!("{} (best {})", statistics.current_streak, statistics.best_streak));
+            ui.end_row();
+        });
+
+        ui.separator();
+        ui.label("Guesses needed");
+
+        let most = statistics.guess_histogram.iter().map(|(_, count)| *count).max().unwrap_or(0).max(1);
+        egui::Grid::new("histogram_grid").num_columns(2).show(ui, |ui| {
+            for (label, count) in &statistics.guess_histogram {
+                ui.label(label);
+                ui.add(ProgressBar::new(*count as f32 / most as f32).text(format!("{}", count)));
+                ui.end_row();
+            }
+        });
+
+        ui.separator();
+        ui.label("Past puzzles");
+
+        let mut play_again = None;
+        let mut replay = None;
+        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+            egui::Grid::new("history_grid").num_columns(5).striped(true).show(ui, |ui| {
+                for record in self.history.iter().rev() {
+                    ui.label(history::format_date(record.finished_at));
+                    ui.label(format!("{} ({})", record.title, record.language));
+                    ui.label(if record.solved { "solved" } else { "abandoned" });
+                    ui.label(format!(
+                        "{} guesses, {:.0}%, {}",
+                        record.guesses.len(),
+                        record.accuracy() * 100.0,
+                        history::format_duration(record.duration_secs)));
+
+                    ui.horizontal(|ui| {
+                        if ui.button("play again").clicked() {
+                            play_again = Some(record.clone());
+                        }
+
+                        let can_replay = !record.guesses.is_empty() && record.article_file.is_some();
+                        if ui.add_enabled(can_replay, Button::new("replay"))
+                            .on_disabled_hover_text("Only games with a saved article can be replayed")
+                            .clicked() {
+                            replay = Some(record.clone());
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        });
+
+        if let Some(record) = play_again {
+            self.play_again(&record);
+        }
+
+        if let Some(record) = replay {
+            self.start_replay(&record);
+        }
+    }
+
+    fn select_guess(&mut self, guess: String) {
+        self.selected_guess = guess;
+        self.hidden_highlight.clear();
+        self.current_occurrence = None;
+        self.scroll_to_selected_guess = true;
+    }
+
+    // Guesses are listed in alphabetical order
+    fn cycle_selected_guess(&mut self, forward: bool) {
+        let count = self.guesses.len();
+
+        if count == 0 {
+            return;
+        }
+
+        let current = self.guesses.iter().position(|guess| *guess == self.selected_guess);
+        let next = match (current, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+        };
+
+        if let Some(guess) = self.guesses.iter().nth(next).cloned() {
+            self.select_guess(guess);
+        }
+    }
+
+    fn toggle_highlight(&mut self) {
+        if !self.selected_guess.is_empty() {
+            self.hidden_highlight = std::mem::take(&mut self.selected_guess);
+        } else if self.guesses.contains(&self.hidden_highlight) {
+            self.selected_guess = std::mem::take(&mut self.hidden_highlight);
+        }
+    }
+
+    fn step_occurrence(&mut self, forward: bool) {
+        let count = self.occurrence_count;
+
+        if self.selected_guess.is_empty() || count == 0 {
+            return;
+        }
+
+        let next = match (self.current_occurrence, forward) {
+            (None, true) => 0,
+            (None, false) => count - 1,
+            (Some(i), true) => (i + 1) % count,
+            (Some(i), false) => (i + count - 1) % count,
+        };
+
+        self.current_occurrence = Some(next);
+        self.jump_to_occurrence = Some(next);
+    }
+
+    // Shortcuts are consumed before the widgets are shown, so that e.g. the
+    // guess box doesn't see them.
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        let pressed = |shortcut: &KeyboardShortcut| ctx.input_mut(|input| input.consume_shortcut(shortcut));
+
+        if pressed(&SHORTCUTS_SHORTCUT) {
+            self.show_shortcuts = !self.show_shortcuts;
+        }
+
+        if self.show_shortcuts && pressed(&CLOSE_SHORTCUT) {
+            self.show_shortcuts = false;
+        }
+
+        if pressed(&SETTINGS_SHORTCUT) {
+            self.show_settings = !self.show_settings;
+        }
+
+        if pressed(&RANDOM_ARTICLE_SHORTCUT) {
+            self.load_random_article();
+        }
+
+        if self.wiki_article.is_none() {
+            return;
+        }
+
+        if pressed(&FOCUS_GUESS_SHORTCUT) {
+            self.focus_on_guess = true;
+        }
+
+        if pressed(&NEXT_GUESS_SHORTCUT) {
+            self.cycle_selected_guess(true);
+        }
+
+        if pressed(&PREVIOUS_GUESS_SHORTCUT) {
+            self.cycle_selected_guess(false);
+        }
+
+        if pressed(&TOGGLE_HIGHLIGHT_SHORTCUT) {
+            self.toggle_highlight();
+        }
+
+        if pressed(&NEXT_OCCURRENCE_SHORTCUT) {
+            self.step_occurrence(true);
+        }
+
+        if pressed(&PREVIOUS_OCCURRENCE_SHORTCUT) {
+            self.step_occurrence(false);
+        }
+
+        // With text in the guess box, Ctrl+Z undoes the typing instead
+        if self.next_guess.is_empty() && self.replay.is_none() && pressed(&UNDO_GUESS_SHORTCUT) {
+            self.undo_guess();
+        }
+    }
+
+    fn show_shortcuts_window(&mut self, ctx: &egui::Context) {
+        let mut show_shortcuts = self.show_shortcuts;
+
+        egui::Window::new("Keyboard shortcuts")
+            .open(&mut show_shortcuts)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                egui::Grid::new("shortcuts_grid").num_columns(2).striped(true).show(ui, |ui| {
+                    for (shortcut, description) in SHORTCUTS.iter() {
+                        ui.label(egui::RichText::new(ctx.format_shortcut(shortcut)).monospace());
+                        ui.label(*description);
+                        ui.end_row();
+                    }
+                });
+            });
+
+        self.show_shortcuts &= show_shortcuts;
     }
 
     fn show_gui(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-	ctx.set_pixels_per_point(2.0);
+        self.handle_shortcuts(ctx);
+
+        self.title_search.poll(
+            ctx,
+            &self.endpoints[self.selected_endpoint],
+            &self.selected_language,
+            &self.known_titles);
+
+        self.update_pack_progress();
+        self.update_history();
+        self.update_replay(ctx);
+        self.poll_word_vectors();
+        self.poll_loading();
+        self.poll_pack_creation();
+        self.poll_languages_refresh();
 
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             self.show_top_bar(ui);
+            self.show_pack_bar(ui);
         });
 
+        let mut show_pack_creator = self.show_pack_creator;
+        egui::Window::new("Create puzzle pack")
+            .open(&mut show_pack_creator)
+            .show(ctx, |ui| {
+                self.show_pack_editor(ui);
+            });
+        self.show_pack_creator &= show_pack_creator;
+
+        let mut show_language_window = self.show_language_window;
+        egui::Window::new("Language")
+            .open(&mut show_language_window)
+            .resizable(false)
+            .show(ctx, |ui| {
+                self.show_language_list(ui);
+            });
+        self.show_language_window &= show_language_window;
+
+        let mut show_settings = self.show_settings;
+        egui::Window::new("Settings")
+            .open(&mut show_settings)
+            .show(ctx, |ui| {
+                self.show_settings_window(ui);
+            });
+        self.show_settings &= show_settings;
+
+        let mut show_statistics = self.show_statistics;
+        egui::Window::new("Statistics")
+            .open(&mut show_statistics)
+            .show(ctx, |ui| {
+                self.show_statistics_window(ui);
+            });
+        self.show_statistics &= show_statistics;
+
+        let mut show_endpoint_settings = self.show_endpoint_settings;
+        egui::Window::new("Wiki endpoints")
+            .open(&mut show_endpoint_settings)
+            .vscroll(true)
+            .show(ctx, |ui| {
+                self.show_endpoint_editor(ui);
+            });
+        self.show_endpoint_settings = show_endpoint_settings;
+
+        self.show_article_chooser(ctx);
+        self.show_shortcuts_window(ctx);
+
+        if self.replay.is_some() {
+            egui::TopBottomPanel::bottom("replay_panel").show(ctx, |ui| {
+                self.show_replay_controls(ui);
+            });
+        }
+
         if let Some(_) = self.wiki_article {
             egui::SidePanel::right("right_panel")
                 .min_width(200.0)
@@ -368,27 +2631,214 @@ impl App {
                 });
         }
 
+        let has_chapters = self.wiki_article
+            .as_ref()
+            .map(|wiki_article| !wiki_article.chapters.is_empty())
+            .unwrap_or(false);
+
+        if has_chapters && self.show_toc {
+            egui::SidePanel::left("toc_panel")
+                .resizable(true)
+                .show_separator_line(true)
+                .show(ctx, |ui| {
+                    if let Some(chapter) = self.show_table_of_contents(ui) {
+                        self.scroll_to_chapter = Some(chapter);
+                    }
+                });
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
-            self.show_article(ui);
+            let actions = self.show_article(ui);
+
+            if actions.unlock_next {
+                self.extra_unlocks += 1;
+            }
+
+            if actions.reached_scroll_target {
+                self.scroll_to_chapter = None;
+            }
+
+            self.occurrence_count = actions.occurrences;
+            self.jump_to_occurrence = None;
+
+            if let Some(target) = actions.follow_link {
+                self.follow_link(&target);
+            }
         });
 
         self.toasts.show(ctx);
     }
 }
 
-impl Default for App {
-    fn default() -> Self {
-        Self {
-            selected_language: String::from("en"),
+const DEFAULT_LANGUAGE_KEY: &str = "default_language";
+const TOKENIZER_OPTIONS_KEY: &str = "tokenizer_options";
+const RANDOM_DIFFICULTY_KEY: &str = "random_difficulty";
+const SHOW_CLOSENESS_KEY: &str = "show_closeness";
+const PROGRESSIVE_REVEAL_KEY: &str = "progressive_reveal";
+const GUESSES_PER_UNLOCK_KEY: &str = "guesses_per_unlock";
+const UNDERLINE_LINKS_KEY: &str = "underline_links";
+const ACCESSIBLE_MODE_KEY: &str = "accessible_mode";
+const CHECK_TYPOS_KEY: &str = "check_typos";
+const APPEARANCE_KEY: &str = "appearance";
+const PHRASE_GUESSES_KEY: &str = "phrase_guesses";
+const REQUIRE_KNOWN_WORDS_KEY: &str = "require_known_words";
+const WORD_LIST_PATHS_KEY: &str = "word_list_paths";
+const WORD_VECTORS_PATH_KEY: &str = "word_vectors_path";
+
+impl App {
+    fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let default_language = cc.storage
+            .and_then(|storage| storage.get_string(DEFAULT_LANGUAGE_KEY))
+            .unwrap_or(String::from("en"));
+
+        let tokenizer_options = cc.storage
+            .and_then(|storage| eframe::get_value(storage, TOKENIZER_OPTIONS_KEY))
+            .unwrap_or_default();
+
+        let random_difficulty = cc.storage
+            .and_then(|storage| eframe::get_value(storage, RANDOM_DIFFICULTY_KEY))
+            .unwrap_or((difficulty::MIN_SCORE, difficulty::MAX_SCORE));
+
+        let progressive_reveal = cc.storage
+            .and_then(|storage| eframe::get_value(storage, PROGRESSIVE_REVEAL_KEY))
+            .unwrap_or(false);
+
+        let guesses_per_unlock = cc.storage
+            .and_then(|storage| eframe::get_value(storage, GUESSES_PER_UNLOCK_KEY))
+            .unwrap_or(10);
+
+        let underline_links = cc.storage
+            .and_then(|storage| eframe::get_value(storage, UNDERLINE_LINKS_KEY))
+            .unwrap_or(false);
+
+        let accessible_mode = cc.storage
+            .and_then(|storage| eframe::get_value(storage, ACCESSIBLE_MODE_KEY))
+            .unwrap_or(false);
+
+        let check_typos = cc.storage
+            .and_then(|storage| eframe::get_value(storage, CHECK_TYPOS_KEY))
+            .unwrap_or(false);
+
+        let phrase_guesses = cc.storage
+            .and_then(|storage| eframe::get_value(storage, PHRASE_GUESSES_KEY))
+            .unwrap_or(false);
+
+        let require_known_words = cc.storage
+            .and_then(|storage| eframe::get_value(storage, REQUIRE_KNOWN_WORDS_KEY))
+            .unwrap_or(false);
+
+        let word_list_paths = cc.storage
+            .and_then(|storage| eframe::get_value(storage, WORD_LIST_PATHS_KEY))
+            .unwrap_or_default();
+
+        let show_closeness = cc.storage
+            .and_then(|storage| eframe::get_value(storage, SHOW_CLOSENESS_KEY))
+            .unwrap_or(false);
+
+        let word_vectors_path: Option<PathBuf> = cc.storage
+            .and_then(|storage| eframe::get_value(storage, WORD_VECTORS_PATH_KEY));
+
+        let appearance: Appearance = cc.storage
+            .and_then(|storage| eframe::get_value(storage, APPEARANCE_KEY))
+            .unwrap_or_default();
+        appearance.apply(&cc.egui_ctx);
+
+        let history = history::load_history()
+            .unwrap_or_else(|e| {
+                println!("Could not load game history: {}", e);
+                Vec::new()
+            });
+
+        let endpoints = wiki_endpoint::load_endpoints()
+            .unwrap_or_else(|e| {
+                println!("Could not load wiki endpoints: {}", e);
+                wiki_endpoint::default_endpoints()
+            });
+
+        let article_endpoint = endpoints[0].clone();
+        let article_language = default_language.clone();
+
+        let mut app = Self {
+            egui_ctx: cc.egui_ctx.clone(),
+            endpoints: endpoints,
+            selected_endpoint: 0,
+            show_endpoint_settings: false,
+            languages: languages::load_languages(),
+            language_filter: String::from(""),
+            show_language_window: false,
+            languages_refresh: None,
+            selected_language: default_language.clone(),
+            default_language: default_language,
+            tokenizer_options: tokenizer_options,
             wiki_article: None,
+            article_endpoint: article_endpoint,
+            article_language: article_language,
             guesses: BTreeSet::new(),
+            guess_log: Vec::new(),
+            game_started: Instant::now(),
+            game_recorded: false,
+            history: history,
+            show_statistics: false,
+            show_settings: false,
+            appearance: appearance,
+            show_shortcuts: false,
+            hidden_highlight: String::from(""),
+            current_occurrence: None,
+            jump_to_occurrence: None,
+            occurrence_count: 0,
+            scroll_to_selected_guess: false,
+            replay: None,
+            loading: None,
 
             toasts: Toasts::new(),
             next_guess: String::from(""),
+            unconfirmed_guess: None,
+            check_typos: check_typos,
+            phrase_guesses: phrase_guesses,
+            require_known_words: require_known_words,
+            word_list_paths: word_list_paths,
+            word_list: None,
             selected_guess: String::from(""),
             focus_on_guess: false,
+            article_chooser: None,
+            hints_used: 0,
+            progressive_reveal: progressive_reveal,
+            guesses_per_unlock: guesses_per_unlock,
+            extra_unlocks: 0,
+            article_generation: 0,
+            show_toc: true,
+            underline_links: underline_links,
+            accessible_mode: accessible_mode,
+            announcement: String::from(""),
+            scroll_to_chapter: None,
+            current_difficulty: None,
+            random_difficulty: random_difficulty,
+
+            pack: None,
+            pack_entry: None,
+            pack_progress: PackProgress::load(),
+            show_pack_creator: false,
+            pack_creator: PackCreator {
+                title: String::from(""),
+                titles: String::from(""),
+                include_articles: true,
+                creating: None,
+            },
             title_text_box: String::from(""),
+            title_search: TitleSearch::new(),
+            known_titles: title_search::load_known_titles(),
+
+            show_closeness: show_closeness,
+            word_vectors_path: word_vectors_path,
+            word_vectors: None,
+            word_vectors_loading: None,
+        };
+
+        if app.show_closeness {
+            app.load_word_vectors();
         }
+
+        app
     }
 }
 
@@ -396,16 +2846,41 @@ impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
         self.show_gui(ctx, frame);
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.record_abandoned_game();
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        storage.set_string(DEFAULT_LANGUAGE_KEY, self.default_language.clone());
+        eframe::set_value(storage, TOKENIZER_OPTIONS_KEY, &self.tokenizer_options);
+        eframe::set_value(storage, RANDOM_DIFFICULTY_KEY, &self.random_difficulty);
+        eframe::set_value(storage, SHOW_CLOSENESS_KEY, &self.show_closeness);
+        eframe::set_value(storage, PROGRESSIVE_REVEAL_KEY, &self.progressive_reveal);
+        eframe::set_value(storage, GUESSES_PER_UNLOCK_KEY, &self.guesses_per_unlock);
+        eframe::set_value(storage, UNDERLINE_LINKS_KEY, &self.underline_links);
+        eframe::set_value(storage, ACCESSIBLE_MODE_KEY, &self.accessible_mode);
+        eframe::set_value(storage, CHECK_TYPOS_KEY, &self.check_typos);
+        eframe::set_value(storage, APPEARANCE_KEY, &self.appearance);
+        eframe::set_value(storage, PHRASE_GUESSES_KEY, &self.phrase_guesses);
+        eframe::set_value(storage, REQUIRE_KNOWN_WORDS_KEY, &self.require_known_words);
+        eframe::set_value(storage, WORD_LIST_PATHS_KEY, &self.word_list_paths);
+        eframe::set_value(storage, WORD_VECTORS_PATH_KEY, &self.word_vectors_path);
+    }
 }
 
 pub fn launch() -> Result<(), eframe::Error> {
     let options = eframe::NativeOptions {
-        initial_window_size: Some(egui::vec2(320.0, 240.0)),
+        initial_window_size: Some(egui::vec2(1280.0, 800.0)),
+        min_window_size: Some(egui::vec2(480.0, 320.0)),
+        // eframe would replace the saved theme whenever the system theme
+        // changes.
+        follow_system_theme: false,
         ..Default::default()
     };
     eframe::run_native(
         "rsdctl",
         options,
-        Box::new(|_cc| Box::<App>::default()),
+        Box::new(|cc| Box::new(App::new(cc))),
     )
 }
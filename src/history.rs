@@ -0,0 +1,220 @@
+use std::io::Write;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::article_format;
+use crate::article_parser::WikiArticle;
+use crate::config;
+
+const HISTORY_FILE: &str = "history.jsonl";
+
+// The articles the games were played on, so that they can be played again
+// and replayed no matter where they came from.
+const ARTICLES_DIR: &str = "history";
+
+// Upper bounds of the guess count buckets in the histogram
+const HISTOGRAM_BUCKETS: [usize; 6] = [10, 25, 50, 100, 200, usize::MAX];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameRecord {
+    pub title: String,
+    pub language: String,
+    pub endpoint: String,
+    pub solved: bool,
+    // In the order they were made, including hints
+    pub guesses: Vec<String>,
+    // Guesses that occur in the article
+    pub hits: usize,
+    pub hints_used: usize,
+    pub duration_secs: u64,
+    // Seconds since the Unix epoch
+    pub finished_at: u64,
+    // File name of the saved article in the articles directory. Games recorded
+    // before articles were saved have none.
+    #[serde(default)]
+    pub article_file: Option<String>,
+}
+
+impl GameRecord {
+    pub fn accuracy(&self) -> f32 {
+        if self.guesses.is_empty() {
+            return 0.0;
+        }
+
+        self.hits as f32 / self.guesses.len() as f32
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
+
+// One JSON object per line, so a game can be added without rewriting the
+// file and a damaged line only loses that game.
+pub fn load_history() -> Result<Vec<GameRecord>> {
+    let path = config::config_file(HISTORY_FILE)?;
+
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = std::fs::read_to_string(&path)?;
+    let mut result = Vec::new();
+
+    for (number, line) in content.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str(line) {
+            Ok(record) => result.push(record),
+            Err(e) => println!("Skipping line {} of {}: {}", number + 1, path.display(), e),
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn append(record: &GameRecord) -> Result<()> {
+    let path = config::config_file(HISTORY_FILE)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)?;
+
+    writeln!(file, "{}", serde_json::to_string(record)?)?;
+
+    Ok(())
+}
+
+// Saves the article of a game and returns the file name to put in its record.
+pub fn save_article(finished_at: u64, article: &WikiArticle) -> Result<String> {
+    let dir = config::config_file(ARTICLES_DIR)?;
+    std::fs::create_dir_all(&dir)?;
+
+    // Several games can end in the same second
+    let mut number = 0;
+    let mut file_name = format!("{}.rsda", finished_at);
+    while dir.join(&file_name).exists() {
+        number += 1;
+        file_name = format!("{}-{}.rsda", finished_at, number);
+    }
+
+    article_format::save(&dir.join(&file_name), article)?;
+    Ok(file_name)
+}
+
+// Returns None for games recorded without their article.
+pub fn load_article(record: &GameRecord) -> Result<Option<WikiArticle>> {
+    let Some(file_name) = &record.article_file else {
+        return Ok(None);
+    };
+
+    let path = config::config_file(ARTICLES_DIR)?.join(file_name);
+    Ok(Some(article_format::load(&path)?))
+}
+
+pub struct Statistics {
+    pub played: usize,
+    pub solved: usize,
+    // Averages over solved games
+    pub average_guesses: f32,
+    pub average_accuracy: f32,
+    pub average_duration_secs: f32,
+    pub average_hints: f32,
+    // Solved games in a row, an abandoned game ends a streak
+    pub current_streak: usize,
+    pub best_streak: usize,
+    // (label, solved games) for every bucket of guess counts
+    pub guess_histogram: Vec<(String, usize)>,
+}
+
+fn average(values: impl Iterator<Item = f32>) -> f32 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), value| (sum + value, count + 1));
+
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f32
+    }
+}
+
+fn bucket_label(index: usize) -> String {
+    let lower = if index == 0 { 1 } else { HISTOGRAM_BUCKETS[index - 1] + 1 };
+
+    match HISTOGRAM_BUCKETS[index] {
+        usize::MAX => format!("{}+", lower),
+        upper => format!("{}–{}", lower, upper),
+    }
+}
+
+pub fn statistics(history: &Vec<GameRecord>) -> Statistics {
+    let solved: Vec<&GameRecord> = history.iter().filter(|record| record.solved).collect();
+
+    let mut current_streak = 0;
+    let mut best_streak = 0;
+    for record in history {
+        if record.solved {
+            current_streak += 1;
+            best_streak = best_streak.max(current_streak);
+        } else {
+            current_streak = 0;
+        }
+    }
+
+    let mut guess_histogram: Vec<(String, usize)> = (0..HISTOGRAM_BUCKETS.len())
+        .map(|index| (bucket_label(index), 0))
+        .collect();
+    for record in &solved {
+        let bucket = HISTOGRAM_BUCKETS
+            .iter()
+            .position(|upper| record.guesses.len() <= *upper)
+            .unwrap_or(HISTOGRAM_BUCKETS.len() - 1);
+        guess_histogram[bucket].1 += 1;
+    }
+
+    Statistics {
+        played: history.len(),
+        solved: solved.len(),
+        average_guesses: average(solved.iter().map(|record| record.guesses.len() as f32)),
+        average_accuracy: average(solved.iter().map(|record| record.accuracy())),
+        average_duration_secs: average(solved.iter().map(|record| record.duration_secs as f32)),
+        average_hints: average(solved.iter().map(|record| record.hints_used as f32)),
+        current_streak: current_streak,
+        best_streak: best_streak,
+        guess_histogram: guess_histogram,
+    }
+}
+
+pub fn format_duration(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+    } else {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+// YYYY-MM-DD in UTC, see http://howardhinnant.github.io/date_algorithms.html
+pub fn format_date(secs: u64) -> String {
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
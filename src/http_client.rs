@@ -0,0 +1,193 @@
+use std::fmt;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use ::reqwest::header::{HeaderMap, RETRY_AFTER};
+use ::reqwest::redirect::Policy;
+use ::reqwest::StatusCode;
+use reqwest::blocking as reqwest;
+
+pub const USER_AGENT: &str = concat!(
+    "rsdctl/", env!("CARGO_PKG_VERSION"),
+    " (https://github.com/jens1o/rsdctl) reqwest"
+);
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub const MAX_RETRIES: u32 = 4;
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+#[derive(Debug)]
+pub enum FetchError {
+    NotFound(String),
+    Network(::reqwest::Error),
+    RateLimited(Option<Duration>),
+    Http(StatusCode),
+    Malformed(String),
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::NotFound(what) => {
+                write!(f, "{} does not exist", what)
+            }
+
+            FetchError::Network(e) => {
+                if e.is_timeout() {
+                    write!(f, "The server did not respond in time")
+                } else if e.is_connect() {
+                    write!(f, "Could not connect to the server, are you offline?")
+                } else {
+                    write!(f, "Network error: {}", e)
+                }
+            }
+
+            FetchError::RateLimited(Some(retry_after)) => {
+                write!(f, "Too many requests, please try again in {} seconds", retry_after.as_secs().max(1))
+            }
+
+            FetchError::RateLimited(None) => {
+                write!(f, "Too many requests, please try again later")
+            }
+
+            FetchError::Http(status) => {
+                write!(f, "The server responded with {}", status)
+            }
+
+            FetchError::Malformed(message) => {
+                write!(f, "Unexpected response from the server: {}", message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FetchError::Network(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<::reqwest::Error> for FetchError {
+    fn from(e: ::reqwest::Error) -> Self {
+        FetchError::Network(e)
+    }
+}
+
+pub fn builder(user_agent: Option<&str>, headers: HeaderMap) -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .user_agent(user_agent.unwrap_or(USER_AGENT))
+        .default_headers(headers)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(REQUEST_TIMEOUT)
+}
+
+pub fn shared_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+    CLIENT.get_or_init(|| {
+        builder(None, HeaderMap::new())
+            .build()
+            .expect("Could not initialize HTTP client")
+    })
+}
+
+pub fn shared_no_redirect_client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+
+    CLIENT.get_or_init(|| {
+        builder(None, HeaderMap::new())
+            .redirect(Policy::none())
+            .build()
+            .expect("Could not initialize HTTP client")
+    })
+}
+
+pub fn get_retry_after(headers: &HeaderMap) -> Option<Duration> {
+    // Only the delay-seconds form is supported, HTTP dates fall back to the
+    // regular backoff.
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+}
+
+pub fn should_retry(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+// What to do about a response to the given attempt: Ok(None) if it can be
+// used, Ok(Some(delay)) to try again after the delay, or the error to give up
+// with.
+pub fn check_response(
+    url: &str,
+    status: StatusCode,
+    headers: &HeaderMap,
+    attempt: u32,
+    backoff: Duration,
+) -> Result<Option<Duration>, FetchError> {
+    if status == StatusCode::NOT_FOUND {
+        return Err(FetchError::NotFound(String::from(url)));
+    }
+
+    if !should_retry(status) {
+        if status.is_success() || status.is_redirection() {
+            return Ok(None);
+        }
+        return Err(FetchError::Http(status));
+    }
+
+    let retry_after = get_retry_after(headers);
+
+    // Don't wait for longer than MAX_BACKOFF, give up instead and let the
+    // user decide when to try again.
+    let gave_up = attempt > MAX_RETRIES
+        || retry_after.map(|r| r > MAX_BACKOFF).unwrap_or(false);
+
+    if gave_up {
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            return Err(FetchError::RateLimited(retry_after));
+        }
+        return Err(FetchError::Http(status));
+    }
+
+    Ok(Some(retry_after.unwrap_or(backoff)))
+}
+
+// Sends a GET request, retrying with exponential backoff on rate limiting,
+// server errors and transient network failures. This can take minutes, so it
+// must not be called from the UI thread.
+pub fn get(client: &reqwest::Client, url: &str) -> Result<reqwest::Response, FetchError> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 0;
+
+    loop {
+        attempt += 1;
+
+        let delay = match client.get(url).send() {
+            Ok(response) => {
+                match check_response(url, response.status(), response.headers(), attempt, backoff)? {
+                    Some(delay) => delay,
+                    None => return Ok(response),
+                }
+            }
+
+            Err(e) => {
+                if attempt > MAX_RETRIES || !(e.is_timeout() || e.is_connect()) {
+                    return Err(FetchError::Network(e));
+                }
+
+                backoff
+            }
+        };
+
+        std::thread::sleep(delay);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
@@ -0,0 +1,70 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+use crate::wikipedia_api;
+
+const LANGUAGES_FILE: &str = "languages.json";
+const BUNDLED_LANGUAGES: &str = include_str!("../assets/wikipedia_languages.tsv");
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Language {
+    pub code: String,
+    pub native_name: String,
+    pub english_name: String,
+}
+
+impl Language {
+    pub fn label(&self) -> String {
+        if self.native_name == self.english_name {
+            format!("{} – {}", self.code, self.native_name)
+        } else {
+            format!("{} – {} ({})", self.code, self.native_name, self.english_name)
+        }
+    }
+
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim().to_lowercase();
+
+        self.code.to_lowercase().starts_with(&filter)
+            || self.native_name.to_lowercase().contains(&filter)
+            || self.english_name.to_lowercase().contains(&filter)
+    }
+}
+
+pub fn bundled_languages() -> Vec<Language> {
+    BUNDLED_LANGUAGES
+        .lines()
+        .filter_map(|line| {
+            let mut columns = line.split('\t');
+            Some(Language {
+                code: String::from(columns.next()?),
+                native_name: String::from(columns.next()?),
+                english_name: String::from(columns.next()?),
+            })
+        })
+        .collect()
+}
+
+// Prefers the list downloaded by refresh_languages over the bundled snapshot.
+pub fn load_languages() -> Vec<Language> {
+    match config::load_json::<Vec<Language>>(LANGUAGES_FILE) {
+        Ok(Some(languages)) if !languages.is_empty() => languages,
+
+        Ok(_) => bundled_languages(),
+
+        Err(e) => {
+            println!("Could not load language list: {}", e);
+            bundled_languages()
+        }
+    }
+}
+
+pub fn refresh_languages() -> Result<Vec<Language>> {
+    let mut languages = wikipedia_api::download_wikipedia_languages()?;
+    languages.sort_by(|a, b| a.code.cmp(&b.code));
+
+    config::save_json(LANGUAGES_FILE, &languages)?;
+
+    Ok(languages)
+}
@@ -4,7 +4,23 @@ use anyhow::Result;
 
 mod gui;
 mod wikipedia_api;
+mod wiki_endpoint;
+mod http_client;
+mod title_search;
+mod config;
+mod languages;
+mod tokenizer;
+mod bidi_layout;
 mod article_parser;
+mod article_format;
+mod puzzle_pack;
+mod difficulty;
+mod word_vectors;
+mod history;
+mod spelling;
+mod phrase;
+mod appearance;
+mod background;
 
 use crate::article_parser::{Section, Token};
 
@@ -12,12 +28,12 @@ use crate::article_parser::{Section, Token};
 fn print_tokens(tokens: &Vec<Token>) {
     for token in tokens {
         match token {
-            Token::Word(w) => {
+            Token::Word(w, _) => {
                 let blanked: String = std::iter::repeat('_').take(w.len()).collect();
                 print!("{}", blanked);
                 // print!("{}", w);
             }
-            Token::NonWord(w) => {
+            Token::NonWord(w, _) => {
                 print!("{}", w);
             }
         }
@@ -0,0 +1,89 @@
+use std::ops::Range;
+
+use crate::article_parser::Token;
+
+// Guesses that are not a single run of letters and digits may span several
+// tokens, like "new york", or "well-known" when hyphens split words.
+pub fn is_phrase(guess: &str) -> bool {
+    guess.trim().chars().any(|c| !c.is_alphanumeric())
+}
+
+// Input in quotes, like "new york", is guessed as a whole instead of word by
+// word.
+pub fn parse_quoted(input: &str) -> Option<String> {
+    let is_open_quote = |c| c == '"' || c == '“';
+    let is_close_quote = |c| c == '"' || c == '”';
+
+    let inner = input
+        .strip_prefix(is_open_quote)
+        .and_then(|rest| rest.strip_suffix(is_close_quote))?;
+
+    let words: Vec<&str> = inner.split_whitespace().collect();
+    Some(words.join(" "))
+}
+
+// Tokens as they are compared with a phrase: words in lower case and any
+// run of whitespace as a single space.
+fn token_text(token: &Token) -> String {
+    match token {
+        Token::Word(w, _) => w.to_lowercase(),
+        Token::NonWord(w, _) => {
+            let mut result = String::new();
+            for c in w.chars() {
+                if !c.is_whitespace() {
+                    result.push(c);
+                } else if !result.ends_with(' ') {
+                    result.push(' ');
+                }
+            }
+            result
+        }
+    }
+}
+
+// The phrase from the tokens of a quoted guess, from its first to its last
+// word. The separators are kept, so that "well-known fact" can still be
+// found when the hyphen is not part of a word.
+pub fn phrase_text(tokens: &[Token]) -> String {
+    let is_word = |token: &Token| matches!(token, Token::Word(..));
+
+    let (Some(first), Some(last)) = (tokens.iter().position(is_word), tokens.iter().rposition(is_word)) else {
+        return String::new();
+    };
+
+    tokens[first..=last].iter().map(token_text).collect()
+}
+
+// The token ranges whose text is the phrase, ignoring case and the kind of
+// whitespace, so "york. new" does not count as "york new". Ranges start and
+// end with a word.
+pub fn find_phrase(phrase: &str, tokens: &[Token]) -> Vec<Range<usize>> {
+    let mut result = Vec::new();
+
+    if phrase.is_empty() {
+        return result;
+    }
+
+    for start in 0..tokens.len() {
+        if !matches!(tokens[start], Token::Word(..)) {
+            continue;
+        }
+
+        let mut text = String::new();
+
+        for (i, token) in tokens.iter().enumerate().skip(start) {
+            text.push_str(&token_text(token));
+
+            if text == phrase && matches!(token, Token::Word(..)) {
+                result.push(start..i + 1);
+                break;
+            }
+
+            if !phrase.starts_with(text.as_str()) {
+                break;
+            }
+        }
+    }
+
+    result
+}
@@ -0,0 +1,233 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::article_parser;
+use crate::article_parser::WikiArticle;
+use crate::config;
+use crate::difficulty;
+use crate::tokenizer::TokenizerOptions;
+use crate::wiki_endpoint::WikiEndpoint;
+use crate::wikipedia_api;
+
+const PROGRESS_FILE: &str = "pack_progress.json";
+
+// Bump whenever the manifest changes in an incompatible way. Embedded
+// articles have a schema version of their own.
+pub const PACK_VERSION: u32 = 1;
+
+// Embedded articles are stored in the versioned form of article_format. One
+// that can't be read anymore is downloaded again instead.
+mod embedded_article {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use serde::ser::Error;
+    use serde_json::Value;
+
+    use crate::article_format;
+    use crate::article_parser::WikiArticle;
+
+    pub fn serialize<S: Serializer>(article: &Option<WikiArticle>, serializer: S) -> Result<S::Ok, S::Error> {
+        let value = match article {
+            Some(article) => Some(article_format::to_value(article).map_err(S::Error::custom)?),
+            None => None,
+        };
+
+        value.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<WikiArticle>, D::Error> {
+        let Some(value) = Option::<Value>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+
+        match article_format::from_value(value) {
+            Ok(article) => Ok(Some(article)),
+
+            Err(e) => {
+                println!("Ignoring the article stored in the puzzle pack: {}", e);
+                Ok(None)
+            }
+        }
+    }
+}
+
+// The wiki the entries are downloaded from. Headers are left out, since they
+// may contain credentials.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PackEndpoint {
+    pub name: String,
+    pub api_url: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackEntry {
+    pub title: String,
+
+    // 0 (trivial) to 100 (very hard)
+    #[serde(default)]
+    pub difficulty: Option<u32>,
+
+    // None means unlimited hints.
+    #[serde(default)]
+    pub allowed_hints: Option<usize>,
+
+    // Pre-parsed article, so the pack can be played offline and isn't
+    // affected by later edits to the wiki.
+    #[serde(default, with = "embedded_article")]
+    pub article: Option<WikiArticle>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PuzzlePack {
+    pub version: u32,
+    pub title: String,
+    pub endpoint: PackEndpoint,
+    pub language: String,
+    pub entries: Vec<PackEntry>,
+
+    // Identifies the pack in the progress file, titles need not be unique.
+    // This is the path the pack was loaded from.
+    #[serde(skip)]
+    pub progress_key: String,
+}
+
+impl PuzzlePack {
+    pub fn new(title: &str, endpoint: &WikiEndpoint, language: &str, entries: Vec<PackEntry>) -> Self {
+        Self {
+            version: PACK_VERSION,
+            title: String::from(title),
+            endpoint: PackEndpoint {
+                name: endpoint.name.clone(),
+                api_url: endpoint.api_url.clone(),
+            },
+            language: String::from(language),
+            entries: entries,
+            progress_key: String::from(""),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+
+        // The version is checked first, like for articles.
+        let value: Value = serde_json::from_str(&content)?;
+
+        let version = value
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .ok_or(anyhow!("Puzzle pack does not contain a version"))?;
+
+        if version != PACK_VERSION as u64 {
+            return Err(anyhow!("Puzzle pack has version {}, but only version {} is supported", version, PACK_VERSION));
+        }
+
+        let mut pack: PuzzlePack = serde_json::from_value(value)?;
+
+        if pack.entries.is_empty() {
+            return Err(anyhow!("Puzzle pack \"{}\" does not contain any entries", pack.title));
+        }
+
+        let path = path.canonicalize().unwrap_or(path.to_path_buf());
+        pack.progress_key = path.to_string_lossy().to_string();
+
+        Ok(pack)
+    }
+
+    // A configured endpoint with the same API URL is preferred, as it may
+    // carry the headers the wiki needs.
+    pub fn wiki_endpoint(&self, configured: &[WikiEndpoint]) -> WikiEndpoint {
+        configured
+            .iter()
+            .find(|endpoint| endpoint.api_url == self.endpoint.api_url)
+            .cloned()
+            .unwrap_or(WikiEndpoint::new(&self.endpoint.name, &self.endpoint.api_url))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    // Fetches all titles from the wiki to estimate their difficulty. With
+    // include_articles the parsed articles are stored in the pack, otherwise
+    // they are downloaded again when played. progress is called with the
+    // index of every title before it is downloaded.
+    pub fn create(
+        pack_title: &str,
+        endpoint: &WikiEndpoint,
+        language: &str,
+        titles: &[String],
+        options: &TokenizerOptions,
+        include_articles: bool,
+        progress: impl Fn(usize),
+    ) -> Result<Self> {
+        let mut entries = Vec::new();
+
+        for (i, title) in titles.iter().enumerate() {
+            progress(i);
+
+            let (page_title, content) = wikipedia_api::download_article(endpoint, language, title)
+                .map_err(|e| anyhow!("{}: {}", title, e))?;
+
+            let article = article_parser::parse(&page_title, &content, options);
+            let difficulty = difficulty::estimate(&article, language);
+
+            entries.push(PackEntry {
+                title: page_title,
+                difficulty: Some(difficulty),
+                allowed_hints: None,
+                article: if include_articles { Some(article) } else { None },
+            });
+        }
+
+        Ok(Self::new(pack_title, endpoint, language, entries))
+    }
+}
+
+// Solved entries of every pack played so far, keyed by the progress key of
+// the pack.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PackProgress {
+    solved: BTreeMap<String, BTreeSet<usize>>,
+}
+
+impl PackProgress {
+    pub fn load() -> Self {
+        match config::load_json(PROGRESS_FILE) {
+            Ok(progress) => progress.unwrap_or_default(),
+
+            Err(e) => {
+                println!("Could not load puzzle pack progress: {}", e);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        config::save_json(PROGRESS_FILE, self)
+    }
+
+    pub fn is_solved(&self, pack: &PuzzlePack, entry: usize) -> bool {
+        self.solved
+            .get(&pack.progress_key)
+            .map(|solved| solved.contains(&entry))
+            .unwrap_or(false)
+    }
+
+    pub fn solved_count(&self, pack: &PuzzlePack) -> usize {
+        self.solved
+            .get(&pack.progress_key)
+            .map(|solved| solved.len())
+            .unwrap_or(0)
+    }
+
+    pub fn mark_solved(&mut self, pack: &PuzzlePack, entry: usize) {
+        self.solved
+            .entry(pack.progress_key.clone())
+            .or_default()
+            .insert(entry);
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::HashSet;
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+
+// True if one word can be turned into the other by inserting, deleting or
+// replacing a single character, or by swapping two adjacent ones.
+pub fn is_one_edit_apart(a: &str, b: &str) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a == b {
+        return false;
+    }
+
+    let (shorter, longer) = if a.len() <= b.len() { (&a, &b) } else { (&b, &a) };
+
+    match longer.len() - shorter.len() {
+        0 => {
+            let differences: Vec<usize> = (0..a.len()).filter(|i| a[*i] != b[*i]).collect();
+
+            match differences.as_slice() {
+                [_] => true,
+                [i, j] => *j == i + 1 && a[*i] == b[*j] && a[*j] == b[*i],
+                _ => false,
+            }
+        }
+
+        1 => {
+            let first_difference = (0..shorter.len())
+                .find(|i| shorter[*i] != longer[*i])
+                .unwrap_or(shorter.len());
+
+            shorter[first_difference..] == longer[first_difference + 1..]
+        }
+
+        _ => false,
+    }
+}
+
+// Why the guess is probably a typo, if it is: it is one edit away from an
+// earlier guess, or one of its words is not known. Guesses that are in the
+// article, or whose words all are, are never suspicious. The words are those
+// of a phrase, or just the guess itself.
+pub fn suspicious_guess<F, G>(guess: &str, words: &[String], earlier_guesses: &[String], in_article: F, is_known: G) -> Option<String>
+where
+    F: Fn(&str) -> bool,
+    G: Fn(&str) -> bool,
+{
+    if in_article(guess) || words.iter().all(|word| in_article(word)) {
+        return None;
+    }
+
+    if let Some(similar) = earlier_guesses.iter().find(|g| is_one_edit_apart(g, guess)) {
+        return Some(format!("\"{}\" is one letter away from your guess \"{}\"", guess, similar));
+    }
+
+    let unknown = words
+        .iter()
+        .filter(|word| !word.chars().all(|c| c.is_numeric()))
+        .find(|word| !is_known(word) && !in_article(word));
+
+    unknown.map(|unknown| format!("\"{}\" is not in the dictionary", unknown))
+}
+
+// Words of a language that may be guessed. Plain lists with one word per line
+// work, as do Hunspell .dic files, whose word count in the first line and
+// affix flags after a slash are ignored.
+pub struct WordList {
+    words: HashSet<String>,
+}
+
+impl WordList {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+
+        let words: HashSet<String> = content
+            .lines()
+            .map(|line| line.split('/').next().unwrap_or("").trim())
+            .filter(|word| !word.is_empty() && !word.chars().all(|c| c.is_ascii_digit()))
+            .map(|word| word.to_lowercase())
+            .collect();
+
+        if words.is_empty() {
+            return Err(anyhow!("{} does not contain any words", path.display()));
+        }
+
+        Ok(Self {
+            words: words,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.contains(&word.to_lowercase())
+    }
+}
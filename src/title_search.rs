@@ -0,0 +1,135 @@
+use std::sync::mpsc::{channel, Receiver};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use eframe::egui;
+
+use crate::config;
+use crate::wiki_endpoint::WikiEndpoint;
+use crate::wikipedia_api;
+
+const DEBOUNCE: Duration = Duration::from_millis(250);
+const MAX_SUGGESTIONS: usize = 10;
+const KNOWN_TITLES_FILE: &str = "known_titles.json";
+
+// Titles of all articles loaded so far, kept between sessions as an index
+// for suggestions while offline.
+pub fn load_known_titles() -> Vec<String> {
+    match config::load_json(KNOWN_TITLES_FILE) {
+        Ok(titles) => titles.unwrap_or_default(),
+
+        Err(e) => {
+            println!("Could not load known titles: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+pub fn save_known_titles(titles: &Vec<String>) -> Result<()> {
+    config::save_json(KNOWN_TITLES_FILE, titles)
+}
+
+// Search-as-you-type for article titles. Requests run on a background thread
+// so typing never blocks on the network; answers to outdated queries are
+// dropped.
+pub struct TitleSearch {
+    query: String,
+    last_edit: Option<Instant>,
+    generation: u64,
+    receiver: Option<Receiver<(u64, Result<Vec<String>>)>>,
+    pub suggestions: Vec<String>,
+}
+
+impl TitleSearch {
+    pub fn new() -> Self {
+        Self {
+            query: String::new(),
+            last_edit: None,
+            generation: 0,
+            receiver: None,
+            suggestions: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.query.clear();
+        self.last_edit = None;
+        self.generation += 1;
+        self.receiver = None;
+        self.suggestions.clear();
+    }
+
+    pub fn edited(&mut self, text: &str) {
+        self.query = String::from(text.trim());
+        self.last_edit = Some(Instant::now());
+        self.generation += 1;
+
+        if self.query.is_empty() {
+            self.receiver = None;
+            self.suggestions.clear();
+        }
+    }
+
+    // known_titles (see load_known_titles) are searched instead when the
+    // wiki can't be reached.
+    pub fn poll(&mut self, ctx: &egui::Context, endpoint: &WikiEndpoint, language: &str, known_titles: &[String]) {
+        if let Some(last_edit) = self.last_edit {
+            let elapsed = last_edit.elapsed();
+
+            if elapsed < DEBOUNCE {
+                ctx.request_repaint_after(DEBOUNCE - elapsed);
+            } else if !self.query.is_empty() {
+                self.last_edit = None;
+                self.spawn_search(ctx, endpoint, language);
+            }
+        }
+
+        let Some(receiver) = &self.receiver else {
+            return;
+        };
+
+        let Ok((generation, result)) = receiver.try_recv() else {
+            return;
+        };
+
+        self.receiver = None;
+
+        if generation != self.generation {
+            return;
+        }
+
+        self.suggestions = match result {
+            Ok(titles) => titles,
+            Err(_) => Self::search_offline(&self.query, known_titles),
+        };
+    }
+
+    fn spawn_search(&mut self, ctx: &egui::Context, endpoint: &WikiEndpoint, language: &str) {
+        let (sender, receiver) = channel();
+        self.receiver = Some(receiver);
+
+        let generation = self.generation;
+        let ctx = ctx.clone();
+        let endpoint = endpoint.clone();
+        let language = String::from(language);
+        let query = self.query.clone();
+
+        std::thread::spawn(move || {
+            let result = wikipedia_api::prefix_search(&endpoint, &language, &query, MAX_SUGGESTIONS);
+            // The receiver is gone if a newer search was started meanwhile.
+            let _ = sender.send((generation, result));
+            ctx.request_repaint();
+        });
+    }
+
+    fn search_offline(query: &str, known_titles: &[String]) -> Vec<String> {
+        let query = query.to_lowercase();
+
+        known_titles
+            .iter()
+            .filter(|title| title.to_lowercase().starts_with(&query))
+            .take(MAX_SUGGESTIONS)
+            .cloned()
+            .collect()
+    }
+}
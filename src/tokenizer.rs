@@ -0,0 +1,276 @@
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::article_parser::{Style, Token};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NumberRule {
+    Guessable,
+    Revealed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TokenizerOptions {
+    // Split Chinese, Thai, Lao, Khmer and Myanmar text into dictionary words
+    // instead of single characters or whole runs of letters. Only has an
+    // effect when built with the dictionary-segmentation feature.
+    pub dictionary_segmentation: bool,
+
+    // "don't" and "O'Brien" are one word instead of two.
+    pub join_apostrophes: bool,
+
+    // "well-known" is one word instead of two.
+    pub join_hyphens: bool,
+
+    pub numbers: NumberRule,
+}
+
+impl Default for TokenizerOptions {
+    fn default() -> Self {
+        Self {
+            dictionary_segmentation: false,
+            join_apostrophes: true,
+            join_hyphens: false,
+            numbers: NumberRule::Guessable,
+        }
+    }
+}
+
+fn is_word(segment: &str) -> bool {
+    segment.chars().any(char::is_alphanumeric)
+}
+
+fn is_number(segment: &str) -> bool {
+    segment.chars().any(char::is_numeric)
+        && segment.chars().all(|c| c.is_numeric() || matches!(c, '.' | ',' | '\'' | '’'))
+}
+
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '’'
+}
+
+fn is_hyphen(segment: &str) -> bool {
+    segment == "-" || segment == "\u{2010}"
+}
+
+fn is_guessable(segment: &str, options: &TokenizerOptions) -> bool {
+    is_word(segment) && !(options.numbers == NumberRule::Revealed && is_number(segment))
+}
+
+fn ends_with_hyphenated_word(result: &Vec<Token>) -> bool {
+    match result.as_slice() {
+        [.., Token::Word(..), Token::NonWord(hyphen, _)] => is_hyphen(hyphen),
+        _ => false,
+    }
+}
+
+// Splits "don't" into "don", "'" and "t".
+fn split_apostrophes(segment: &str) -> Vec<&str> {
+    let mut result = Vec::new();
+    let mut start = 0;
+
+    for (i, c) in segment.char_indices() {
+        if is_apostrophe(c) {
+            if i > start {
+                result.push(&segment[start..i]);
+            }
+            result.push(&segment[i..i + c.len_utf8()]);
+            start = i + c.len_utf8();
+        }
+    }
+
+    if start < segment.len() {
+        result.push(&segment[start..]);
+    }
+
+    result
+}
+
+fn is_han(c: char) -> bool {
+    matches!(c,
+        '\u{3400}'..='\u{4DBF}' |
+        '\u{4E00}'..='\u{9FFF}' |
+        '\u{F900}'..='\u{FAFF}' |
+        '\u{20000}'..='\u{2FA1F}')
+}
+
+// Scripts written without spaces between words, for which UAX #29 breaks
+// after every character (Thai, Lao, Myanmar, Khmer).
+fn is_complex_context(c: char) -> bool {
+    matches!(c,
+        '\u{0E00}'..='\u{0EFF}' |
+        '\u{1000}'..='\u{109F}' |
+        '\u{1780}'..='\u{17FF}' |
+        '\u{19E0}'..='\u{19FF}' |
+        '\u{AA60}'..='\u{AA7F}')
+}
+
+fn is_complex_context_word(word: &str) -> bool {
+    word.chars().all(is_complex_context)
+}
+
+// Keeps the tokens alternating between Word and NonWord, which the rest of
+// the program relies on. Two adjacent words (e.g. Chinese characters) are
+// separated by an empty NonWord.
+fn push_token(result: &mut Vec<Token>, segment: &str, options: &TokenizerOptions) {
+    let segment_is_word = is_guessable(segment, options);
+
+    if segment_is_word && options.join_hyphens && ends_with_hyphenated_word(result) {
+        let Some(Token::NonWord(hyphen, _)) = result.pop() else {
+            unreachable!();
+        };
+
+        if let Some(Token::Word(previous, _)) = result.last_mut() {
+            previous.push_str(&hyphen);
+            previous.push_str(segment);
+        }
+        return;
+    }
+
+    match (result.last_mut(), segment_is_word) {
+        (Some(Token::NonWord(previous, _)), false) => {
+            previous.push_str(segment);
+        }
+
+        (Some(Token::Word(previous, _)), true) => {
+            if !segments_complex_context(options) && is_complex_context_word(previous) && is_complex_context_word(segment) {
+                // Without a dictionary the best guess for these scripts is
+                // that a run of letters is one word.
+                previous.push_str(segment);
+            } else {
+                result.push(Token::NonWord(String::new(), Style::default()));
+                result.push(Token::Word(String::from(segment), Style::default()));
+            }
+        }
+
+        (_, true) => {
+            result.push(Token::Word(String::from(segment), Style::default()));
+        }
+
+        (_, false) => {
+            result.push(Token::NonWord(String::from(segment), Style::default()));
+        }
+    }
+}
+
+// UAX #29 keeps apostrophes inside words, so they are only split off when
+// requested.
+fn push_segment(result: &mut Vec<Token>, segment: &str, options: &TokenizerOptions) {
+    if !options.join_apostrophes && is_word(segment) && segment.contains(is_apostrophe) {
+        for part in split_apostrophes(segment) {
+            push_token(result, part, options);
+        }
+    } else {
+        push_token(result, segment, options);
+    }
+}
+
+fn segments_complex_context(options: &TokenizerOptions) -> bool {
+    cfg!(feature = "dictionary-segmentation") && options.dictionary_segmentation
+}
+
+#[cfg(feature = "dictionary-segmentation")]
+fn segment_han(run: &str) -> Vec<&str> {
+    use std::sync::OnceLock;
+
+    static JIEBA: OnceLock<jieba_rs::Jieba> = OnceLock::new();
+
+    JIEBA.get_or_init(jieba_rs::Jieba::new).cut(run, false)
+}
+
+#[cfg(not(feature = "dictionary-segmentation"))]
+fn segment_han(run: &str) -> Vec<&str> {
+    run.split_word_bounds().collect()
+}
+
+#[cfg(feature = "dictionary-segmentation")]
+fn segment_complex_context(run: &str) -> Vec<&str> {
+    thread_local! {
+        // The segmenter can't be shared between threads.
+        static SEGMENTER: icu_segmenter::WordSegmenter = icu_segmenter::WordSegmenter::new_dictionary();
+    }
+
+    let breakpoints: Vec<usize> = SEGMENTER.with(|segmenter| segmenter.segment_str(run).collect());
+
+    breakpoints
+        .windows(2)
+        .map(|pair| &run[pair[0]..pair[1]])
+        .collect()
+}
+
+#[cfg(not(feature = "dictionary-segmentation"))]
+fn segment_complex_context(run: &str) -> Vec<&str> {
+    run.split_word_bounds().collect()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Script {
+    Han,
+    ComplexContext,
+    Other,
+}
+
+fn script_of(c: char) -> Script {
+    if is_han(c) {
+        Script::Han
+    } else if is_complex_context(c) {
+        Script::ComplexContext
+    } else {
+        Script::Other
+    }
+}
+
+// Splits the input into runs of characters that need the same kind of
+// segmentation.
+fn split_script_runs(input: &str) -> Vec<(Script, &str)> {
+    let mut result = Vec::new();
+    let mut run_start = 0;
+    let mut run_script = Script::Other;
+
+    for (i, c) in input.char_indices() {
+        let script = script_of(c);
+
+        if i > run_start && script != run_script {
+            result.push((run_script, &input[run_start..i]));
+            run_start = i;
+        }
+
+        if i == run_start {
+            run_script = script;
+        }
+    }
+
+    if run_start < input.len() {
+        result.push((run_script, &input[run_start..]));
+    }
+
+    result
+}
+
+// Splits the input into Words and NonWords along Unicode (UAX #29) word
+// boundaries.
+pub fn chop_into_tokens(input: &str, options: &TokenizerOptions) -> Vec<Token> {
+    let mut result: Vec<Token> = Vec::new();
+
+    if !options.dictionary_segmentation {
+        for segment in input.split_word_bounds() {
+            push_segment(&mut result, segment, options);
+        }
+        return result;
+    }
+
+    for (script, run) in split_script_runs(input) {
+        let segments = match script {
+            Script::Han => segment_han(run),
+            Script::ComplexContext => segment_complex_context(run),
+            Script::Other => run.split_word_bounds().collect(),
+        };
+
+        for segment in segments {
+            push_segment(&mut result, segment, options);
+        }
+    }
+
+    result
+}
@@ -0,0 +1,68 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::config;
+
+const ENDPOINTS_FILE: &str = "endpoints.json";
+const WIKIPEDIA_API_URL: &str = "https://{language}.wikipedia.org/w/api.php";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WikiEndpoint {
+    pub name: String,
+
+    // URL of the wiki's api.php. A "{language}" placeholder is replaced by
+    // the selected language code.
+    pub api_url: String,
+
+    #[serde(default)]
+    pub user_agent: Option<String>,
+
+    // Extra headers sent with every request, e.g. for authentication.
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+}
+
+impl WikiEndpoint {
+    pub fn new(name: &str, api_url: &str) -> Self {
+        Self {
+            name: String::from(name),
+            api_url: String::from(api_url),
+            user_agent: None,
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn uses_language(&self) -> bool {
+        self.api_url.contains("{language}")
+    }
+
+    pub fn api_url_for(&self, language: &str) -> String {
+        self.api_url.replace("{language}", language)
+    }
+}
+
+pub fn wikipedia() -> WikiEndpoint {
+    WikiEndpoint::new("Wikipedia", WIKIPEDIA_API_URL)
+}
+
+pub fn default_endpoints() -> Vec<WikiEndpoint> {
+    vec![
+        wikipedia(),
+        WikiEndpoint::new("Wiktionary", "https://{language}.wiktionary.org/w/api.php"),
+        WikiEndpoint::new("Wikivoyage", "https://{language}.wikivoyage.org/w/api.php"),
+    ]
+}
+
+pub fn load_endpoints() -> Result<Vec<WikiEndpoint>> {
+    let endpoints: Vec<WikiEndpoint> = config::load_json(ENDPOINTS_FILE)?.unwrap_or(Vec::new());
+
+    if endpoints.is_empty() {
+        return Ok(default_endpoints());
+    }
+
+    Ok(endpoints)
+}
+
+pub fn save_endpoints(endpoints: &Vec<WikiEndpoint>) -> Result<()> {
+    config::save_json(ENDPOINTS_FILE, endpoints)
+}
@@ -1,45 +1,281 @@
-use anyhow::{anyhow, Result};
-use ::reqwest::redirect::Policy;
+use std::borrow::Cow;
+use std::fmt;
+
+use anyhow::Result;
+use ::reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use ::reqwest::Url;
 use reqwest::blocking as reqwest;
 use serde_json::Value;
 
-pub fn download_article(language: &str, title: &str) -> Result<(String, String)> {
-    let query = format!("https://{}.wikipedia.org/w/api.php?action=parse&page={}&prop=wikitext&formatversion=2&format=json", language, title);
+use crate::http_client;
+use crate::languages::Language;
+use crate::http_client::FetchError;
+use crate::wiki_endpoint::WikiEndpoint;
+
+fn endpoint_client(endpoint: &WikiEndpoint) -> Result<reqwest::Client> {
+    let mut headers = HeaderMap::new();
+
+    for (name, value) in &endpoint.headers {
+        let name = HeaderName::from_bytes(name.trim().as_bytes())?;
+        let value = HeaderValue::from_str(value.trim())?;
+        headers.insert(name, value);
+    }
+
+    let client = http_client::builder(endpoint.user_agent.as_deref(), headers)
+        .build()?;
+
+    Ok(client)
+}
 
-    let content = reqwest::get(query)?;
+fn get_json(client: &reqwest::Client, url: &str) -> Result<Value, FetchError> {
+    let content = http_client::get(client, url)?;
     let content = content.text()?;
-    let content: Value = serde_json::from_str(&content)?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| FetchError::Malformed(format!("invalid JSON: {}", e)))
+}
+
+#[derive(Debug)]
+pub enum ArticleError {
+    Missing { title: String, suggestions: Vec<String> },
+    Disambiguation { title: String, options: Vec<String> },
+    Api { code: String, info: String },
+}
+
+impl fmt::Display for ArticleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ArticleError::Missing { title, suggestions } => {
+                write!(f, "The article \"{}\" does not exist", title)?;
+                if !suggestions.is_empty() {
+                    let suggestions: String = suggestions
+                        .iter()
+                        .map(|s| s.as_str())
+                        .intersperse(", ")
+                        .collect();
+                    write!(f, ". Did you mean: {}?", suggestions)?;
+                }
+                Ok(())
+            }
+
+            ArticleError::Disambiguation { title, .. } => {
+                write!(f, "\"{}\" is a disambiguation page", title)
+            }
+
+            ArticleError::Api { code, info } => {
+                write!(f, "The wiki returned an error ({}): {}", code, info)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ArticleError {}
+
+fn get_client(endpoint: &WikiEndpoint) -> Result<Cow<'static, reqwest::Client>> {
+    if endpoint.user_agent.is_none() && endpoint.headers.is_empty() {
+        Ok(Cow::Borrowed(http_client::shared_client()))
+    } else {
+        Ok(Cow::Owned(endpoint_client(endpoint)?))
+    }
+}
+
+fn api_url(endpoint: &WikiEndpoint, language: &str, params: &[(&str, &str)]) -> Result<Url> {
+    let url = Url::parse_with_params(&endpoint.api_url_for(language), params)?;
+    Ok(url)
+}
+
+pub fn search_titles(endpoint: &WikiEndpoint, language: &str, search: &str, limit: usize) -> Result<Vec<String>> {
+    let limit = limit.to_string();
+    let query = api_url(endpoint, language, &[
+        ("action", "query"),
+        ("list", "search"),
+        ("srsearch", search),
+        ("srlimit", limit.as_str()),
+        ("srprop", ""),
+        ("formatversion", "2"),
+        ("format", "json"),
+    ])?;
+
+    let client = get_client(endpoint)?;
+    let content = get_json(&client, query.as_str())?;
+
+    let titles = content
+        .pointer("/query/search")
+        .and_then(|val| val.as_array())
+        .map(|results| {
+            results
+                .iter()
+                .filter_map(|result| result.get("title").and_then(|t| t.as_str()))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(titles)
+}
+
+pub fn prefix_search(endpoint: &WikiEndpoint, language: &str, prefix: &str, limit: usize) -> Result<Vec<String>> {
+    let limit = limit.to_string();
+    let query = api_url(endpoint, language, &[
+        ("action", "opensearch"),
+        ("search", prefix),
+        ("limit", limit.as_str()),
+        ("namespace", "0"),
+        ("redirects", "resolve"),
+        ("format", "json"),
+    ])?;
+
+    let client = get_client(endpoint)?;
+    let content = get_json(&client, query.as_str())?;
+
+    // opensearch answers with [query, [titles], [descriptions], [urls]]
+    let titles = content
+        .get(1)
+        .and_then(|val| val.as_array())
+        .ok_or(FetchError::Malformed(String::from("opensearch response did not contain titles")))?
+        .iter()
+        .filter_map(|title| title.as_str())
+        .map(String::from)
+        .collect();
+
+    Ok(titles)
+}
+
+fn get_article_links(content: &Value) -> Vec<String> {
+    content
+        .pointer("/parse/links")
+        .and_then(|val| val.as_array())
+        .map(|links| {
+            links
+                .iter()
+                .filter(|link| link.get("ns").and_then(|ns| ns.as_i64()) == Some(0))
+                .filter(|link| link.get("exists").and_then(|e| e.as_bool()).unwrap_or(false))
+                .filter_map(|link| link.get("title").and_then(|t| t.as_str()))
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub fn download_article(endpoint: &WikiEndpoint, language: &str, title: &str) -> Result<(String, String)> {
+    let query = api_url(endpoint, language, &[
+        ("action", "parse"),
+        ("page", title),
+        ("prop", "wikitext|properties|links"),
+        ("redirects", "1"),
+        ("formatversion", "2"),
+        ("format", "json"),
+    ])?;
+
+    let client = get_client(endpoint)?;
+    let content = get_json(&client, query.as_str())?;
+
+    if let Some(error) = content.get("error") {
+        let code = error.get("code").and_then(|c| c.as_str()).unwrap_or("");
+        let info = error.get("info").and_then(|i| i.as_str()).unwrap_or("");
+
+        if code == "missingtitle" {
+            // Suggestions are best effort, the article is missing either way.
+            let suggestions = search_titles(endpoint, language, title, 5).unwrap_or_default();
+
+            return Err(ArticleError::Missing {
+                title: String::from(title),
+                suggestions: suggestions,
+            }.into());
+        }
+
+        return Err(ArticleError::Api {
+            code: String::from(code),
+            info: String::from(info),
+        }.into());
+    }
 
     let wikitext = content
         .pointer("/parse/wikitext")
         .and_then(|val| val.as_str())
-        .ok_or(anyhow!("JSON response did not contain wikitext"))?;
+        .ok_or(FetchError::Malformed(String::from("JSON response did not contain wikitext")))?;
 
     let page_title = content
         .pointer("/parse/title")
         .and_then(|val| val.as_str())
-        .ok_or(anyhow!("JSON response did not contain page title"))?;
+        .ok_or(FetchError::Malformed(String::from("JSON response did not contain page title")))?;
+
+    let is_disambiguation = content
+        .pointer("/parse/properties/disambiguation")
+        .is_some();
+
+    if is_disambiguation {
+        return Err(ArticleError::Disambiguation {
+            title: String::from(page_title),
+            options: get_article_links(&content),
+        }.into());
+    }
 
     Ok((String::from(page_title), String::from(wikitext)))
 }
 
+pub fn download_wikipedia_languages() -> Result<Vec<Language>> {
+    let query = "https://meta.wikimedia.org/w/api.php?action=sitematrix&smtype=language&smlangprop=code|name|localname|site&smsiteprop=code&formatversion=2&format=json";
+
+    let content = get_json(http_client::shared_client(), query)?;
+
+    let sitematrix = content
+        .get("sitematrix")
+        .and_then(|val| val.as_object())
+        .ok_or(FetchError::Malformed(String::from("JSON response did not contain sitematrix")))?;
+
+    let mut languages = Vec::new();
+
+    // Besides the numbered language entries the sitematrix contains a
+    // "count" field, which is skipped because it has no code.
+    for entry in sitematrix.values() {
+        let has_wikipedia = entry
+            .get("site")
+            .and_then(|sites| sites.as_array())
+            .map(|sites| {
+                sites.iter().any(|site| {
+                    site.get("code").and_then(|c| c.as_str()) == Some("wiki")
+                        && site.get("closed").is_none()
+                })
+            })
+            .unwrap_or(false);
+
+        let code = entry.get("code").and_then(|c| c.as_str());
+        let native_name = entry.get("name").and_then(|n| n.as_str());
+        let english_name = entry.get("localname").and_then(|n| n.as_str());
+
+        if let (true, Some(code), Some(native_name), Some(english_name)) = (has_wikipedia, code, native_name, english_name) {
+            languages.push(Language {
+                code: String::from(code),
+                native_name: String::from(native_name),
+                english_name: String::from(english_name),
+            });
+        }
+    }
+
+    Ok(languages)
+}
+
 pub fn random_english_article() -> Result<String> {
     let query = "https://randomincategory.toolforge.org/?category=All_Wikipedia_level-4_vital_articles&server=en.wikipedia.org&cmnamespace=&cmtype=&returntype=subject&debug=0";
 
-    let client = reqwest::Client::builder()
-        .user_agent("curl/8.1.2")
-        .redirect(Policy::none())
-        .build()?;
-
-    let response = client.get(query).send()?;
-    let redir_header = response.headers().get("location").ok_or(anyhow!("Could not find location header"))?;
-    let redir_location = redir_header.to_str()?;
+    let response = http_client::get(http_client::shared_no_redirect_client(), query)?;
+    let redir_header = response
+        .headers()
+        .get("location")
+        .ok_or(FetchError::Malformed(String::from("Could not find location header")))?;
+    let redir_location = redir_header
+        .to_str()
+        .map_err(|e| FetchError::Malformed(format!("{}", e)))?;
 
     let redir_prefix = "https://en.wikipedia.org/wiki/";
 
     if !redir_location.starts_with(redir_prefix) {
-        return Err(anyhow!("Redirect had unexpected format: {}", redir_location));
+        return Err(FetchError::Malformed(format!("Redirect had unexpected format: {}", redir_location)).into());
     }
 
-    Ok(String::from(&redir_location[redir_prefix.len()..]))
+    let title = urlencoding::decode(&redir_location[redir_prefix.len()..])
+        .map_err(|e| FetchError::Malformed(format!("{}", e)))?;
+
+    Ok(title.replace('_', " "))
 }
@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+
+// fastText .vec files are sorted by frequency, so the rare words at the end
+// can be skipped to keep memory usage and loading time reasonable.
+pub const MAX_WORDS: usize = 200_000;
+
+// Word embeddings in the text format used by fastText and word2vec: a header
+// line "<count> <dimension>" followed by one "<word> <v1> <v2> ..." per line.
+pub struct WordVectors {
+    vectors: HashMap<String, Vec<f32>>,
+}
+
+fn normalize(vector: &mut [f32]) {
+    let length = vector.iter().map(|v| v * v).sum::<f32>().sqrt();
+
+    if length > 0.0 {
+        for v in vector.iter_mut() {
+            *v /= length;
+        }
+    }
+}
+
+impl WordVectors {
+    pub fn load(path: &Path) -> Result<Self> {
+        let mut lines = BufReader::new(File::open(path)?).lines();
+
+        let header = lines.next().ok_or(anyhow!("{} is empty", path.display()))??;
+        let dimension: usize = header
+            .split_whitespace()
+            .nth(1)
+            .and_then(|d| d.parse().ok())
+            .ok_or(anyhow!("{} does not start with a \"<count> <dimension>\" header", path.display()))?;
+
+        let mut vectors = HashMap::new();
+
+        for line in lines.take(MAX_WORDS) {
+            let line = line?;
+            let mut parts = line.split(' ');
+
+            let Some(word) = parts.next() else {
+                continue;
+            };
+
+            let mut vector: Vec<f32> = parts
+                .filter(|part| !part.is_empty())
+                .map(|part| part.parse::<f32>())
+                .collect::<Result<_, _>>()?;
+
+            if vector.len() != dimension {
+                return Err(anyhow!("Vector for \"{}\" has {} instead of {} dimensions", word, vector.len(), dimension));
+            }
+
+            // Vectors are stored normalized, so the cosine similarity is just
+            // the dot product.
+            normalize(&mut vector);
+
+            // Keep the most frequent spelling if a word occurs in several
+            // cases.
+            vectors.entry(word.to_lowercase()).or_insert(vector);
+        }
+
+        Ok(Self {
+            vectors: vectors,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.vectors.contains_key(&word.to_lowercase())
+    }
+
+    // Cosine similarity between -1 and 1, None if a word is unknown.
+    pub fn similarity(&self, a: &str, b: &str) -> Option<f32> {
+        let a = self.vectors.get(&a.to_lowercase())?;
+        let b = self.vectors.get(&b.to_lowercase())?;
+
+        Some(a.iter().zip(b.iter()).map(|(x, y)| x * y).sum())
+    }
+
+    pub fn closest_similarity(&self, word: &str, targets: &[String]) -> Option<f32> {
+        targets
+            .iter()
+            .filter_map(|target| self.similarity(word, target))
+            .fold(None, |best: Option<f32>, s| Some(best.map_or(s, |b| b.max(s))))
+    }
+}
@@ -0,0 +1,91 @@
+#![feature(iter_intersperse)]
+#![allow(dead_code)]
+
+mod common;
+
+#[path = "../src/article_format.rs"]
+mod article_format;
+#[path = "../src/article_parser.rs"]
+mod article_parser;
+#[path = "../src/tokenizer.rs"]
+mod tokenizer;
+
+use std::path::Path;
+
+use crate::article_format::SCHEMA_VERSION;
+use crate::article_parser::WikiArticle;
+use crate::common::TempDir;
+use crate::tokenizer::TokenizerOptions;
+
+fn article() -> WikiArticle {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join("headings_and_lists.wiki");
+    article_parser::parse_file(&fixture, &TokenizerOptions::default()).unwrap()
+}
+
+#[test]
+fn json_round_trip() {
+    let article = article();
+    let json = article_format::to_json(&article).unwrap();
+
+    assert_eq!(article_format::from_json(&json).unwrap(), article);
+}
+
+#[test]
+fn binary_round_trip() {
+    let article = article();
+    let bytes = article_format::to_binary(&article).unwrap();
+
+    assert_eq!(article_format::from_binary(&bytes).unwrap(), article);
+}
+
+#[test]
+fn other_schema_versions_are_rejected() {
+    let article = article();
+
+    let json = article_format::to_json(&article)
+        .unwrap()
+        .replacen(&format!("\"version\": {}", SCHEMA_VERSION), &format!("\"version\": {}", SCHEMA_VERSION + 1), 1);
+    let error = article_format::from_json(&json).unwrap_err();
+    assert!(error.to_string().contains("schema version"), "{}", error);
+
+    let mut bytes = article_format::to_binary(&article).unwrap();
+    bytes[4..8].copy_from_slice(&(SCHEMA_VERSION + 1).to_le_bytes());
+    let error = article_format::from_binary(&bytes).unwrap_err();
+    assert!(error.to_string().contains("schema version"), "{}", error);
+}
+
+#[test]
+fn wrong_magic_bytes_are_rejected() {
+    let mut bytes = article_format::to_binary(&article()).unwrap();
+    bytes[0..4].copy_from_slice(b"RSDX");
+
+    let error = article_format::from_binary(&bytes).unwrap_err();
+    assert!(error.to_string().contains("Not a binary"), "{}", error);
+}
+
+#[test]
+fn files_are_saved_in_the_format_of_their_extension() {
+    let article = article();
+    let dir = TempDir::new();
+
+    for file_name in ["article.json", "article.rsda"] {
+        let path = dir.file(file_name);
+        article_format::save(&path, &article).unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(bytes.starts_with(b"RSDA"), file_name.ends_with(".rsda"));
+        assert_eq!(article_format::load(&path).unwrap(), article);
+    }
+}
+
+#[test]
+fn versions_beyond_u32_are_rejected() {
+    // Would wrap around to the current version if truncated
+    let version = (1u64 << 32) + SCHEMA_VERSION as u64;
+    let json = article_format::to_json(&article())
+        .unwrap()
+        .replacen(&format!("\"version\": {}", SCHEMA_VERSION), &format!("\"version\": {}", version), 1);
+
+    let error = article_format::from_json(&json).unwrap_err();
+    assert!(error.to_string().contains("invalid schema version"), "{}", error);
+}
@@ -0,0 +1,224 @@
+#![feature(iter_intersperse)]
+#![allow(dead_code)]
+
+// The parser lives in a binary crate, so its modules are compiled into the
+// test crate directly.
+mod common;
+
+#[path = "../src/article_parser.rs"]
+mod article_parser;
+#[path = "../src/tokenizer.rs"]
+mod tokenizer;
+
+use std::path::{Path, PathBuf};
+
+use crate::article_parser::{Section, Token};
+use crate::common::TempDir;
+use crate::tokenizer::TokenizerOptions;
+
+fn fixtures() -> Vec<PathBuf> {
+    let fixtures_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures");
+
+    let mut result: Vec<PathBuf> = std::fs::read_dir(fixtures_dir)
+        .expect("fixtures directory is missing")
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    result.sort();
+    result
+}
+
+// Compares against the golden snapshot of the fixture. Set UPDATE_SNAPSHOTS=1
+// to record new snapshots or re-record all of them after an intended change
+// to the parser.
+fn assert_snapshot(fixture: &Path, actual: &str) {
+    let name = fixture.file_name().unwrap().to_str().unwrap();
+    let snapshot = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("snapshots")
+        .join(format!("{}.snap", name));
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(snapshot.parent().unwrap()).unwrap();
+        std::fs::write(&snapshot, actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&snapshot).unwrap_or_else(|_| {
+        panic!("snapshot {} is missing, run with UPDATE_SNAPSHOTS=1 to record it", snapshot.display())
+    });
+    assert_eq!(expected, actual, "snapshot {} does not match", snapshot.display());
+}
+
+#[test]
+fn fixtures_match_snapshots() {
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty());
+
+    for fixture in fixtures {
+        let article = article_parser::parse_file(&fixture, &TokenizerOptions::default())
+            .unwrap_or_else(|e| panic!("could not parse {}: {}", fixture.display(), e));
+
+        assert_snapshot(&fixture, &format!("{:#?}\n", article));
+    }
+}
+
+#[test]
+fn title_line_is_not_part_of_the_content() {
+    for fixture in fixtures() {
+        let article = article_parser::parse_file(&fixture, &TokenizerOptions::default()).unwrap();
+
+        let title = text(&article.title);
+        assert!(!title.is_empty(), "{} has no title", fixture.display());
+        assert!(!article.lead.is_empty() || !article.chapters.is_empty(), "{} has no content", fixture.display());
+
+        let first_line = match article.lead.first() {
+            Some(Section::Heading(_, tokens)) | Some(Section::Paragraph(tokens)) => text(tokens),
+            _ => String::new(),
+        };
+        assert_ne!(first_line.trim(), title, "{} repeats its title in the content", fixture.display());
+    }
+}
+
+#[test]
+fn inline_formatting_is_kept_on_tokens() {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join("links.wiki");
+    let article = article_parser::parse_file(&fixture, &TokenizerOptions::default()).unwrap();
+
+    let Some(Section::Paragraph(tokens)) = article.lead.first() else {
+        panic!("{} does not start with a paragraph", fixture.display());
+    };
+
+    let style_of = |word: &str| {
+        tokens
+            .iter()
+            .find_map(|token| match token {
+                Token::Word(w, style) if w == word => Some(style.clone()),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("{} is not in the first paragraph", word))
+    };
+
+    assert!(style_of("Augusta").bold);
+    assert!(!style_of("English").bold);
+    assert_eq!(style_of("Engine").link.as_deref(), Some("Analytical Engine"));
+    assert_eq!(style_of("writer").link, None);
+}
+
+fn parse_text(file_name: &str, content: &str) -> article_parser::WikiArticle {
+    let dir = TempDir::new();
+    let path = dir.write(file_name, content);
+
+    article_parser::parse_file(&path, &TokenizerOptions::default()).unwrap()
+}
+
+fn text(tokens: &[Token]) -> String {
+    tokens
+        .iter()
+        .map(|token| match token {
+            Token::Word(w, _) | Token::NonWord(w, _) => w.as_str(),
+        })
+        .collect()
+}
+
+#[test]
+fn only_paired_markdown_markers_are_removed() {
+    let article = parse_text(
+        "markers.md",
+        "# Notes\n\nUse snake_case and 2*3, but **bold**, _italic_ and `a_b` lose their markers.\n",
+    );
+
+    let Some(Section::Paragraph(tokens)) = article.lead.first() else {
+        panic!("the note does not start with a paragraph");
+    };
+
+    assert_eq!(text(tokens), "Use snake_case and 2*3, but bold, italic and a_b lose their markers.");
+}
+
+#[test]
+fn plain_text_titles_keep_hashes() {
+    let article = parse_text("c_sharp.txt", "C#\n\nA programming language.\n");
+    assert_eq!(text(&article.title), "C#");
+
+    let article = parse_text("c_sharp.md", "# C# #\n\nA programming language.\n");
+    assert_eq!(text(&article.title), "C#");
+
+    let article = parse_text("c_sharp.wiki", "== C# ==\n\nA programming language.\n");
+    assert_eq!(text(&article.title), "C#");
+}
+
+fn outline(content: &str) -> article_parser::WikiArticle {
+    article_parser::parse("Outline", content, &TokenizerOptions::default())
+}
+
+fn paragraphs(sections: &[Section]) -> Vec<String> {
+    sections
+        .iter()
+        .filter_map(|section| match section {
+            Section::Paragraph(tokens) => Some(text(tokens)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn content_before_the_first_heading_is_the_lead() {
+    let article = outline("Lead text.\n\n== History ==\nOld times.\n");
+
+    assert_eq!(paragraphs(&article.lead), vec!["Lead text."]);
+    assert_eq!(article.chapters.len(), 1);
+    assert_eq!(text(&article.chapters[0].heading), "History");
+    assert_eq!(paragraphs(&article.chapters[0].content), vec!["Old times."]);
+}
+
+#[test]
+fn chapters_are_nested_by_level() {
+    let article = outline(
+        "== History ==\nOld times.\n\n=== Origins ===\nFirst.\n\n=== Growth ===\nThen.\n\n==== Decline ====\nLast.\n\n== Geography ==\nHills.\n",
+    );
+
+    let headings: Vec<String> = article.chapters.iter().map(|c| text(&c.heading)).collect();
+    assert_eq!(headings, vec!["History", "Geography"]);
+
+    let history = &article.chapters[0];
+    assert_eq!(history.level, 2);
+    assert_eq!(paragraphs(&history.content), vec!["Old times."]);
+    assert_eq!(history.count(), 4);
+
+    let subheadings: Vec<String> = history.subchapters.iter().map(|c| text(&c.heading)).collect();
+    assert_eq!(subheadings, vec!["Origins", "Growth"]);
+    assert_eq!(paragraphs(&history.subchapters[1].content), vec!["Then."]);
+
+    let decline = &history.subchapters[1].subchapters[0];
+    assert_eq!(decline.level, 4);
+    assert_eq!(paragraphs(&decline.content), vec!["Last."]);
+
+    assert!(article.chapters[1].subchapters.is_empty());
+    assert_eq!(paragraphs(&article.chapters[1].content), vec!["Hills."]);
+}
+
+#[test]
+fn skipped_levels_nest_under_the_last_open_chapter() {
+    let article = outline("== History ==\n\n==== Details ====\nSmall print.\n\n=== Origins ===\nFirst.\n");
+
+    assert_eq!(article.chapters.len(), 1);
+    let history = &article.chapters[0];
+
+    let subheadings: Vec<String> = history.subchapters.iter().map(|c| text(&c.heading)).collect();
+    assert_eq!(subheadings, vec!["Details", "Origins"]);
+    assert_eq!(history.subchapters[0].level, 4);
+    assert_eq!(paragraphs(&history.subchapters[0].content), vec!["Small print."]);
+    assert_eq!(history.subchapters[1].level, 3);
+}
+
+#[test]
+fn headings_without_a_body_are_kept() {
+    let article = outline("== See also ==\n\n== References ==\nSome.\n\n== Notes ==\n");
+
+    let headings: Vec<String> = article.chapters.iter().map(|c| text(&c.heading)).collect();
+    assert_eq!(headings, vec!["See also", "References", "Notes"]);
+    assert!(article.chapters[0].content.is_empty());
+    assert!(article.chapters[0].subchapters.is_empty());
+    assert_eq!(paragraphs(&article.chapters[1].content), vec!["Some."]);
+    assert!(article.chapters[2].content.is_empty());
+    assert!(article.lead.is_empty());
+}
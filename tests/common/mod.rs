@@ -0,0 +1,48 @@
+// Helpers shared by the integration tests. Not every test uses all of them.
+#![allow(dead_code)]
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+// A directory of its own for every test, so that tests running in parallel
+// don't see each other's files. It is removed once dropped.
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub fn new() -> Self {
+        let number = NEXT_DIR.fetch_add(1, Ordering::SeqCst);
+        let path = std::env::temp_dir().join(format!("rsdctl-test-{}-{}", std::process::id(), number));
+
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        Self {
+            path: path,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn file(&self, file_name: &str) -> PathBuf {
+        self.path.join(file_name)
+    }
+
+    // Returns the path of the new file.
+    pub fn write(&self, file_name: &str, content: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.file(file_name);
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
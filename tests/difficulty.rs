@@ -0,0 +1,78 @@
+#![feature(iter_intersperse)]
+#![allow(dead_code)]
+
+#[path = "../src/article_parser.rs"]
+mod article_parser;
+#[path = "../src/difficulty.rs"]
+mod difficulty;
+#[path = "../src/tokenizer.rs"]
+mod tokenizer;
+
+use crate::article_parser::WikiArticle;
+use crate::tokenizer::TokenizerOptions;
+
+fn article(title: &str, content: &str) -> WikiArticle {
+    article_parser::parse(title, content, &TokenizerOptions::default())
+}
+
+fn repeat(sentence: &str, times: usize) -> String {
+    vec![sentence; times].join("\n\n")
+}
+
+#[test]
+fn scores_stay_in_range() {
+    let empty = article("", "");
+    let score = difficulty::estimate(&empty, "en");
+    assert!((difficulty::MIN_SCORE..=difficulty::MAX_SCORE).contains(&score));
+
+    let long = article("Water", &repeat("Water is a liquid. Water is found in every river and sea.", 500));
+    let score = difficulty::estimate(&long, "en");
+    assert!((difficulty::MIN_SCORE..=difficulty::MAX_SCORE).contains(&score));
+}
+
+#[test]
+fn title_words_that_occur_often_are_easier() {
+    let often = article("River", &repeat("The river flows into the sea. A river is long.", 20));
+    let never = article("River", &repeat("The water flows into the sea. A stream is long.", 20));
+
+    assert!(difficulty::estimate(&often, "en") < difficulty::estimate(&never, "en"));
+}
+
+#[test]
+fn longer_articles_are_easier() {
+    let short = article("River", "The river flows into the sea.");
+    let long = article("River", &repeat("The river flows into the sea.", 200));
+
+    assert!(difficulty::estimate(&long, "en") < difficulty::estimate(&short, "en"));
+}
+
+#[test]
+fn headings_make_articles_easier() {
+    let flat = article("River", &repeat("Section\nThe river flows into the sea.", 10));
+    let structured = article("River", &repeat("== Section ==\nThe river flows into the sea.", 10));
+
+    assert!(difficulty::estimate(&structured, "en") < difficulty::estimate(&flat, "en"));
+}
+
+#[test]
+fn rare_title_words_are_harder() {
+    let content = repeat("It flows into the sea.", 10);
+
+    // English uses the list of common words.
+    let common = article("Water", &content);
+    let rare = article("Zygote", &content);
+    assert!(difficulty::estimate(&common, "en") < difficulty::estimate(&rare, "en"));
+
+    // Other languages take long words as rare.
+    let short = article("Fluss", &content);
+    let long = article("Wasserfall", &content);
+    assert!(difficulty::estimate(&short, "de") < difficulty::estimate(&long, "de"));
+}
+
+#[test]
+fn labels_cover_all_scores() {
+    assert_eq!(difficulty::label(difficulty::MIN_SCORE), "easy");
+    assert_eq!(difficulty::label(30), "medium");
+    assert_eq!(difficulty::label(60), "hard");
+    assert_eq!(difficulty::label(difficulty::MAX_SCORE), "very hard");
+}
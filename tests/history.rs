@@ -0,0 +1,113 @@
+#![feature(iter_intersperse)]
+#![allow(dead_code)]
+
+#[path = "../src/article_format.rs"]
+mod article_format;
+#[path = "../src/article_parser.rs"]
+mod article_parser;
+#[path = "../src/config.rs"]
+mod config;
+#[path = "../src/history.rs"]
+mod history;
+#[path = "../src/tokenizer.rs"]
+mod tokenizer;
+
+use crate::history::GameRecord;
+
+fn game(solved: bool, guesses: usize) -> GameRecord {
+    GameRecord {
+        title: String::from("Berlin"),
+        language: String::from("en"),
+        endpoint: String::from("Wikipedia"),
+        solved: solved,
+        guesses: (0..guesses).map(|i| format!("guess{}", i)).collect(),
+        hits: guesses / 2,
+        hints_used: 1,
+        duration_secs: 60,
+        finished_at: 0,
+        article_file: None,
+    }
+}
+
+fn histogram_counts(history: &Vec<GameRecord>) -> Vec<usize> {
+    history::statistics(history)
+        .guess_histogram
+        .iter()
+        .map(|(_, count)| *count)
+        .collect()
+}
+
+#[test]
+fn empty_history() {
+    let statistics = history::statistics(&Vec::new());
+
+    assert_eq!(statistics.played, 0);
+    assert_eq!(statistics.solved, 0);
+    assert_eq!(statistics.average_guesses, 0.0);
+    assert_eq!(statistics.current_streak, 0);
+    assert_eq!(statistics.best_streak, 0);
+    assert!(statistics.guess_histogram.iter().all(|(_, count)| *count == 0));
+}
+
+#[test]
+fn abandoned_games_end_streaks() {
+    let history = vec![
+        game(true, 5),
+        game(true, 5),
+        game(true, 5),
+        game(false, 5),
+        game(true, 5),
+    ];
+    let statistics = history::statistics(&history);
+
+    assert_eq!(statistics.played, 5);
+    assert_eq!(statistics.solved, 4);
+    assert_eq!(statistics.current_streak, 1);
+    assert_eq!(statistics.best_streak, 3);
+
+    let statistics = history::statistics(&vec![game(true, 5), game(false, 5)]);
+    assert_eq!(statistics.current_streak, 0);
+    assert_eq!(statistics.best_streak, 1);
+}
+
+#[test]
+fn averages_only_count_solved_games() {
+    let history = vec![game(true, 10), game(true, 20), game(false, 1000)];
+    let statistics = history::statistics(&history);
+
+    assert_eq!(statistics.average_guesses, 15.0);
+    assert_eq!(statistics.average_accuracy, 0.5);
+    assert_eq!(statistics.average_duration_secs, 60.0);
+    assert_eq!(statistics.average_hints, 1.0);
+}
+
+#[test]
+fn histogram_buckets_solved_games_by_guesses() {
+    let labels: Vec<String> = history::statistics(&Vec::new())
+        .guess_histogram
+        .into_iter()
+        .map(|(label, _)| label)
+        .collect();
+    assert_eq!(labels, ["1–10", "11–25", "26–50", "51–100", "101–200", "201+"]);
+
+    let history = vec![
+        game(true, 1),
+        game(true, 10),
+        game(true, 11),
+        game(true, 100),
+        game(true, 201),
+        game(true, 5000),
+        // Abandoned games are not part of the histogram
+        game(false, 30),
+    ];
+    assert_eq!(histogram_counts(&history), [2, 1, 0, 1, 0, 2]);
+}
+
+#[test]
+fn records_without_an_article_file_still_load() {
+    let line = r#"{"title":"Berlin","language":"en","endpoint":"Wikipedia","solved":true,"guesses":["city"],"hits":1,"hints_used":0,"duration_secs":60,"finished_at":0}"#;
+    let record: GameRecord = serde_json::from_str(line).unwrap();
+
+    assert_eq!(record.article_file, None);
+    assert!(history::load_article(&record).unwrap().is_none());
+}
@@ -0,0 +1,122 @@
+#![allow(dead_code)]
+
+#[path = "../src/http_client.rs"]
+mod http_client;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::time::Duration;
+
+use reqwest::header::{HeaderMap, HeaderValue, RETRY_AFTER};
+use reqwest::StatusCode;
+
+use crate::http_client::{check_response, get_retry_after, should_retry, FetchError, MAX_BACKOFF, MAX_RETRIES};
+
+const URL: &str = "https://example.org/w/api.php";
+const BACKOFF: Duration = Duration::from_millis(500);
+
+fn retry_after(value: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(RETRY_AFTER, HeaderValue::from_str(value).unwrap());
+    headers
+}
+
+#[test]
+fn rate_limits_and_server_errors_are_retried() {
+    assert!(should_retry(StatusCode::TOO_MANY_REQUESTS));
+    assert!(should_retry(StatusCode::INTERNAL_SERVER_ERROR));
+    assert!(should_retry(StatusCode::SERVICE_UNAVAILABLE));
+
+    assert!(!should_retry(StatusCode::OK));
+    assert!(!should_retry(StatusCode::BAD_REQUEST));
+    assert!(!should_retry(StatusCode::FORBIDDEN));
+    assert!(!should_retry(StatusCode::NOT_FOUND));
+}
+
+#[test]
+fn retry_after_is_read_in_seconds() {
+    assert_eq!(get_retry_after(&retry_after("120")), Some(Duration::from_secs(120)));
+    assert_eq!(get_retry_after(&retry_after(" 5 ")), Some(Duration::from_secs(5)));
+
+    // HTTP dates fall back to the regular backoff
+    assert_eq!(get_retry_after(&retry_after("Wed, 21 Oct 2015 07:28:00 GMT")), None);
+    assert_eq!(get_retry_after(&HeaderMap::new()), None);
+}
+
+#[test]
+fn usable_responses_are_accepted() {
+    let headers = HeaderMap::new();
+
+    assert!(matches!(check_response(URL, StatusCode::OK, &headers, 1, BACKOFF), Ok(None)));
+    assert!(matches!(check_response(URL, StatusCode::FOUND, &headers, 1, BACKOFF), Ok(None)));
+}
+
+#[test]
+fn client_errors_fail_immediately() {
+    let headers = HeaderMap::new();
+
+    match check_response(URL, StatusCode::NOT_FOUND, &headers, 1, BACKOFF) {
+        Err(FetchError::NotFound(url)) => assert_eq!(url, URL),
+        other => panic!("unexpected {:?}", other),
+    }
+
+    assert!(matches!(
+        check_response(URL, StatusCode::FORBIDDEN, &headers, 1, BACKOFF),
+        Err(FetchError::Http(StatusCode::FORBIDDEN))));
+}
+
+#[test]
+fn retries_wait_for_retry_after_or_the_backoff() {
+    assert_eq!(
+        check_response(URL, StatusCode::TOO_MANY_REQUESTS, &retry_after("2"), 1, BACKOFF).unwrap(),
+        Some(Duration::from_secs(2)));
+
+    assert_eq!(
+        check_response(URL, StatusCode::SERVICE_UNAVAILABLE, &HeaderMap::new(), 1, BACKOFF).unwrap(),
+        Some(BACKOFF));
+}
+
+#[test]
+fn retries_give_up_eventually() {
+    let too_long = format!("{}", MAX_BACKOFF.as_secs() + 1);
+
+    assert!(matches!(
+        check_response(URL, StatusCode::TOO_MANY_REQUESTS, &retry_after(&too_long), 1, BACKOFF),
+        Err(FetchError::RateLimited(Some(_)))));
+
+    assert!(matches!(
+        check_response(URL, StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new(), MAX_RETRIES + 1, BACKOFF),
+        Err(FetchError::RateLimited(None))));
+
+    assert!(matches!(
+        check_response(URL, StatusCode::BAD_GATEWAY, &HeaderMap::new(), MAX_RETRIES + 1, BACKOFF),
+        Err(FetchError::Http(StatusCode::BAD_GATEWAY))));
+}
+
+#[test]
+fn get_retries_until_the_server_recovers() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://127.0.0.1:{}/w/api.php", listener.local_addr().unwrap().port());
+
+    let server = std::thread::spawn(move || {
+        for response in ["HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0", "HTTP/1.1 200 OK"] {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim_end().is_empty() {
+                    break;
+                }
+            }
+
+            write!(stream, "{}\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok", response).unwrap();
+        }
+    });
+
+    let response = http_client::get(http_client::shared_client(), &url).unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    server.join().unwrap();
+}
@@ -0,0 +1,88 @@
+#![feature(iter_intersperse)]
+#![allow(dead_code)]
+
+#[path = "../src/article_parser.rs"]
+mod article_parser;
+#[path = "../src/phrase.rs"]
+mod phrase;
+#[path = "../src/tokenizer.rs"]
+mod tokenizer;
+
+use crate::phrase::{find_phrase, is_phrase, parse_quoted, phrase_text};
+use crate::tokenizer::{chop_into_tokens, TokenizerOptions};
+
+fn count(phrase: &str, text: &str) -> usize {
+    find_phrase(phrase, &chop_into_tokens(text, &TokenizerOptions::default())).len()
+}
+
+#[test]
+fn phrases_need_more_than_one_word() {
+    assert!(is_phrase("new york"));
+    assert!(!is_phrase("york"));
+    assert!(!is_phrase(" york "));
+}
+
+#[test]
+fn quoted_input_is_one_phrase() {
+    assert_eq!(parse_quoted("\"new  york\""), Some(String::from("new york")));
+    assert_eq!(parse_quoted("“new york”"), Some(String::from("new york")));
+    assert_eq!(parse_quoted("new york"), None);
+}
+
+#[test]
+fn phrases_match_consecutive_words() {
+    assert_eq!(count("new york", "New York is in New York State, not in new\nYork."), 3);
+    assert_eq!(count("new york", "A new building in York."), 0);
+    assert_eq!(count("york new", "York. New"), 0);
+}
+
+#[test]
+fn phrase_ranges_cover_the_words_and_separators() {
+    let tokens = chop_into_tokens("in New York", &TokenizerOptions::default());
+    assert_eq!(find_phrase("new york", &tokens), vec![2..5]);
+}
+
+fn quoted_phrase(input: &str, options: &TokenizerOptions) -> String {
+    let quoted = parse_quoted(input).unwrap();
+    phrase_text(&chop_into_tokens(&quoted, options))
+}
+
+#[test]
+fn quoted_phrases_keep_their_separators() {
+    let options = TokenizerOptions::default();
+
+    assert_eq!(quoted_phrase("\"New  York,\"", &options), "new york");
+    assert_eq!(quoted_phrase("\"well-known fact\"", &options), "well-known fact");
+    assert_eq!(quoted_phrase("\"...\"", &options), "");
+}
+
+#[test]
+fn hyphenated_phrases_are_found() {
+    let options = TokenizerOptions { join_hyphens: false, ..TokenizerOptions::default() };
+    let phrase = quoted_phrase("\"well-known fact\"", &options);
+    assert!(is_phrase(&phrase));
+
+    let tokens = chop_into_tokens("A well-known fact, and a well known fact.", &options);
+    assert_eq!(find_phrase(&phrase, &tokens).len(), 1);
+
+    // The same phrase is found when hyphens join words
+    let options = TokenizerOptions { join_hyphens: true, ..TokenizerOptions::default() };
+    let tokens = chop_into_tokens("A well-known fact.", &options);
+    assert_eq!(find_phrase(&quoted_phrase("\"well-known fact\"", &options), &tokens).len(), 1);
+
+    // A quoted word that the tokenizer splits is a phrase as well
+    let options = TokenizerOptions { join_hyphens: false, ..TokenizerOptions::default() };
+    let phrase = quoted_phrase("\"well-known\"", &options);
+    assert!(is_phrase(&phrase));
+    assert_eq!(find_phrase(&phrase, &chop_into_tokens("It is well-known.", &options)), vec![4..7]);
+}
+
+#[test]
+fn phrases_with_apostrophes_are_found() {
+    let options = TokenizerOptions { join_apostrophes: false, ..TokenizerOptions::default() };
+    let phrase = quoted_phrase("\"O'Brien family\"", &options);
+    assert_eq!(phrase, "o'brien family");
+
+    let tokens = chop_into_tokens("The O'Brien family and the O Brien family.", &options);
+    assert_eq!(find_phrase(&phrase, &tokens).len(), 1);
+}
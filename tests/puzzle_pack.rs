@@ -0,0 +1,164 @@
+#![feature(iter_intersperse)]
+#![allow(dead_code)]
+
+mod common;
+
+#[path = "../src/article_format.rs"]
+mod article_format;
+#[path = "../src/article_parser.rs"]
+mod article_parser;
+#[path = "../src/config.rs"]
+mod config;
+#[path = "../src/difficulty.rs"]
+mod difficulty;
+#[path = "../src/http_client.rs"]
+mod http_client;
+#[path = "../src/languages.rs"]
+mod languages;
+#[path = "../src/puzzle_pack.rs"]
+mod puzzle_pack;
+#[path = "../src/tokenizer.rs"]
+mod tokenizer;
+#[path = "../src/wiki_endpoint.rs"]
+mod wiki_endpoint;
+#[path = "../src/wikipedia_api.rs"]
+mod wikipedia_api;
+
+use std::path::Path;
+
+use crate::article_parser::WikiArticle;
+use crate::common::TempDir;
+use crate::puzzle_pack::{PackEntry, PackProgress, PuzzlePack, PACK_VERSION};
+use crate::tokenizer::TokenizerOptions;
+use crate::wiki_endpoint::WikiEndpoint;
+
+fn article() -> WikiArticle {
+    let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join("basic_paragraphs.wiki");
+    article_parser::parse_file(&fixture, &TokenizerOptions::default()).unwrap()
+}
+
+fn entry(title: &str, article: Option<WikiArticle>) -> PackEntry {
+    PackEntry {
+        title: String::from(title),
+        difficulty: Some(40),
+        allowed_hints: Some(3),
+        article: article,
+    }
+}
+
+fn pack(title: &str) -> PuzzlePack {
+    let endpoint = WikiEndpoint::new("Wiktionary", "https://{language}.wiktionary.org/w/api.php");
+
+    PuzzlePack::new(title, &endpoint, "de", vec![
+        entry("Rust (programming language)", Some(article())),
+        entry("Haus", None),
+    ])
+}
+
+#[test]
+fn packs_round_trip() {
+    let dir = TempDir::new();
+    let path = dir.file("pack.json");
+
+    let pack = pack("Languages");
+    pack.save(&path).unwrap();
+    let loaded = PuzzlePack::load(&path).unwrap();
+
+    assert_eq!(loaded.version, PACK_VERSION);
+    assert_eq!(loaded.title, "Languages");
+    assert_eq!(loaded.language, "de");
+    assert_eq!(loaded.endpoint, pack.endpoint);
+    assert_eq!(loaded.entries.len(), 2);
+
+    // With and without an embedded article
+    assert_eq!(loaded.entries[0].article.as_ref(), Some(&article()));
+    assert_eq!(loaded.entries[0].difficulty, Some(40));
+    assert_eq!(loaded.entries[0].allowed_hints, Some(3));
+    assert_eq!(loaded.entries[1].title, "Haus");
+    assert_eq!(loaded.entries[1].article, None);
+}
+
+#[test]
+fn other_pack_versions_are_rejected() {
+    let dir = TempDir::new();
+    let path = dir.file("pack.json");
+    pack("Languages").save(&path).unwrap();
+
+    let content = std::fs::read_to_string(&path)
+        .unwrap()
+        .replacen(&format!("\"version\": {}", PACK_VERSION), &format!("\"version\": {}", PACK_VERSION + 1), 1);
+    std::fs::write(&path, content).unwrap();
+
+    let error = PuzzlePack::load(&path).unwrap_err();
+    assert!(error.to_string().contains("only version"), "{}", error);
+}
+
+#[test]
+fn packs_without_entries_are_rejected() {
+    let dir = TempDir::new();
+    let path = dir.file("pack.json");
+
+    let mut pack = pack("Empty");
+    pack.entries.clear();
+    pack.save(&path).unwrap();
+
+    assert!(PuzzlePack::load(&path).is_err());
+}
+
+#[test]
+fn unreadable_embedded_articles_are_downloaded_instead() {
+    let dir = TempDir::new();
+    let path = dir.file("pack.json");
+    pack("Languages").save(&path).unwrap();
+
+    // An article schema this version does not know leaves the pack readable
+    let content = std::fs::read_to_string(&path)
+        .unwrap()
+        .replacen(&format!("\"version\": {}", article_format::SCHEMA_VERSION), "\"version\": 999", 1);
+    std::fs::write(&path, content).unwrap();
+
+    let loaded = PuzzlePack::load(&path).unwrap();
+    assert_eq!(loaded.entries[0].article, None);
+    assert_eq!(loaded.entries[0].title, "Rust (programming language)");
+}
+
+#[test]
+fn entries_are_downloaded_from_the_wiki_of_the_pack() {
+    let pack = pack("Languages");
+
+    let endpoint = pack.wiki_endpoint(&wiki_endpoint::default_endpoints());
+    assert_eq!(endpoint.api_url, "https://{language}.wiktionary.org/w/api.php");
+
+    // Even if it is not configured
+    let endpoint = pack.wiki_endpoint(&[wiki_endpoint::wikipedia()]);
+    assert_eq!(endpoint.name, "Wiktionary");
+    assert_eq!(endpoint.api_url, "https://{language}.wiktionary.org/w/api.php");
+}
+
+#[test]
+fn progress_is_kept_per_pack_file() {
+    let dir = TempDir::new();
+    let first_path = dir.file("first.json");
+    let second_path = dir.file("second.json");
+
+    // Same title, different packs
+    pack("Languages").save(&first_path).unwrap();
+    pack("Languages").save(&second_path).unwrap();
+    let first = PuzzlePack::load(&first_path).unwrap();
+    let second = PuzzlePack::load(&second_path).unwrap();
+
+    let mut progress = PackProgress::default();
+    progress.mark_solved(&first, 1);
+
+    assert!(progress.is_solved(&first, 1));
+    assert!(!progress.is_solved(&first, 0));
+    assert_eq!(progress.solved_count(&first), 1);
+
+    assert!(!progress.is_solved(&second, 1));
+    assert_eq!(progress.solved_count(&second), 0);
+
+    // Solving an entry twice counts once
+    progress.mark_solved(&first, 1);
+    progress.mark_solved(&first, 0);
+    assert_eq!(progress.solved_count(&first), 2);
+}
@@ -0,0 +1,127 @@
+mod common;
+
+#[path = "../src/spelling.rs"]
+mod spelling;
+
+use crate::common::TempDir;
+use crate::spelling::{is_one_edit_apart, suspicious_guess, WordList};
+
+#[test]
+fn identical_words_are_not_one_edit_apart() {
+    assert!(!is_one_edit_apart("berlin", "berlin"));
+    assert!(!is_one_edit_apart("", ""));
+}
+
+#[test]
+fn insertions_and_deletions() {
+    assert!(is_one_edit_apart("berlin", "berlins"));
+    assert!(is_one_edit_apart("berlin", "bberlin"));
+    assert!(is_one_edit_apart("berlin", "brlin"));
+    assert!(is_one_edit_apart("brlin", "berlin"));
+    assert!(is_one_edit_apart("a", ""));
+
+    assert!(!is_one_edit_apart("berlin", "brln"));
+    assert!(!is_one_edit_apart("berlin", "berlinxx"));
+}
+
+#[test]
+fn substitutions() {
+    assert!(is_one_edit_apart("berlin", "merlin"));
+    assert!(is_one_edit_apart("berlin", "berlim"));
+    assert!(is_one_edit_apart("köln", "koln"));
+
+    assert!(!is_one_edit_apart("berlin", "merlim"));
+}
+
+#[test]
+fn transpositions() {
+    assert!(is_one_edit_apart("berlin", "brelin"));
+    assert!(is_one_edit_apart("berlin", "berlni"));
+
+    // Only adjacent characters may be swapped
+    assert!(!is_one_edit_apart("berlin", "nerlib"));
+    assert!(!is_one_edit_apart("abc", "bca"));
+}
+
+fn word_list(file_name: &str, content: &str) -> anyhow::Result<WordList> {
+    let dir = TempDir::new();
+    WordList::load(&dir.write(file_name, content))
+}
+
+#[test]
+fn plain_word_lists_are_loaded() {
+    let word_list = word_list("words.txt", "Berlin\nriver\n\n  sea  \n").unwrap();
+
+    assert_eq!(word_list.len(), 3);
+    assert!(word_list.contains("berlin"));
+    assert!(word_list.contains("River"));
+    assert!(word_list.contains("sea"));
+    assert!(!word_list.contains("lake"));
+}
+
+#[test]
+fn hunspell_dictionaries_are_loaded() {
+    let word_list = word_list("de_DE.dic", "4\nHaus/SEP\nFluss/Sp\nStraße\nBerlin/S\n").unwrap();
+
+    // The word count is not a word, and the affix flags are not part of one
+    assert_eq!(word_list.len(), 4);
+    assert!(!word_list.contains("4"));
+    assert!(word_list.contains("haus"));
+    assert!(word_list.contains("Fluss"));
+    assert!(word_list.contains("straße"));
+    assert!(!word_list.contains("haus/sep"));
+}
+
+#[test]
+fn empty_word_lists_are_rejected() {
+    assert!(word_list("empty.dic", "0\n\n").is_err());
+}
+
+fn in_article(word: &str) -> bool {
+    ["berlin", "spree", "river spree"].contains(&word)
+}
+
+fn is_known(word: &str) -> bool {
+    ["berlin", "berlins", "river", "wall"].contains(&word)
+}
+
+fn check(guess: &str, earlier: &[String]) -> Option<String> {
+    let words: Vec<String> = guess.split(|c: char| !c.is_alphanumeric()).map(String::from).collect();
+    suspicious_guess(guess, &words, earlier, in_article, is_known)
+}
+
+#[test]
+fn guesses_in_the_article_are_not_suspicious() {
+    let earlier = vec![String::from("berlins")];
+
+    // One edit away from "berlins", but in the article
+    assert_eq!(check("berlin", &earlier), None);
+    // Not in the dictionary, but in the article
+    assert_eq!(check("spree", &earlier), None);
+    assert_eq!(check("river spree", &earlier), None);
+    assert_eq!(check("berlin spree", &earlier), None);
+}
+
+#[test]
+fn guesses_close_to_earlier_ones_are_suspicious() {
+    let earlier = vec![String::from("wall")];
+
+    let warning = check("walls", &earlier).unwrap();
+    assert_eq!(warning, "\"walls\" is one letter away from your guess \"wall\"");
+}
+
+#[test]
+fn unknown_words_are_suspicious() {
+    let warning = check("rivr", &[]).unwrap();
+    assert_eq!(warning, "\"rivr\" is not in the dictionary");
+
+    // Words of a phrase that are in the article are fine
+    let warning = check("spree rivr", &[]).unwrap();
+    assert_eq!(warning, "\"rivr\" is not in the dictionary");
+
+    assert_eq!(check("river wall", &[]), None);
+    assert_eq!(check("1989", &[]), None);
+
+    // Only the words of a phrase are looked up, not its separators
+    assert_eq!(check("river-wall", &[]), None);
+}
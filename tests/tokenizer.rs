@@ -0,0 +1,143 @@
+#![feature(iter_intersperse)]
+#![allow(dead_code)]
+
+#[path = "../src/article_parser.rs"]
+mod article_parser;
+#[path = "../src/tokenizer.rs"]
+mod tokenizer;
+
+use proptest::prelude::*;
+
+use crate::article_parser::Token;
+use crate::tokenizer::{chop_into_tokens, NumberRule, TokenizerOptions};
+
+fn concat(tokens: &Vec<Token>) -> String {
+    let mut result = String::new();
+
+    for token in tokens {
+        match token {
+            Token::Word(w, _) | Token::NonWord(w, _) => result.push_str(w),
+        }
+    }
+
+    result
+}
+
+fn words(input: &str, options: &TokenizerOptions) -> Vec<String> {
+    chop_into_tokens(input, options)
+        .into_iter()
+        .filter_map(|token| match token {
+            Token::Word(w, _) => Some(w),
+            Token::NonWord(..) => None,
+        })
+        .collect()
+}
+
+fn options() -> impl Strategy<Value = TokenizerOptions> {
+    (any::<bool>(), any::<bool>(), any::<bool>(), any::<bool>()).prop_map(
+        |(dictionary_segmentation, join_apostrophes, join_hyphens, reveal_numbers)| {
+            TokenizerOptions {
+                dictionary_segmentation: dictionary_segmentation,
+                join_apostrophes: join_apostrophes,
+                join_hyphens: join_hyphens,
+                numbers: if reveal_numbers { NumberRule::Revealed } else { NumberRule::Guessable },
+            }
+        })
+}
+
+fn text() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<String>(),
+        "[a-zA-Z0-9 ,.'’\\-]{0,40}",
+        "[a-zA-Zäöü東京都は日本の首都תלאביבกรุงเทพ 。,.'\\-]{0,40}",
+    ]
+}
+
+proptest! {
+    #[test]
+    fn tokens_concatenate_to_input(input in text(), options in options()) {
+        let tokens = chop_into_tokens(&input, &options);
+        prop_assert_eq!(concat(&tokens), input);
+    }
+
+    #[test]
+    fn tokens_alternate_between_words_and_non_words(input in text(), options in options()) {
+        let tokens = chop_into_tokens(&input, &options);
+
+        for pair in tokens.windows(2) {
+            let alternates = matches!(pair,
+                [Token::Word(..), Token::NonWord(..)] | [Token::NonWord(..), Token::Word(..)]);
+            prop_assert!(alternates, "{:?}", tokens);
+        }
+    }
+
+    #[test]
+    fn only_separators_between_words_are_empty(input in text(), options in options()) {
+        let tokens = chop_into_tokens(&input, &options);
+
+        for (i, token) in tokens.iter().enumerate() {
+            match token {
+                Token::Word(w, _) => {
+                    prop_assert!(!w.is_empty());
+                }
+
+                Token::NonWord(w, _) => {
+                    let between_words = i > 0 && i + 1 < tokens.len();
+                    prop_assert!(!w.is_empty() || between_words, "{:?}", tokens);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn contractions_are_configurable() {
+    let joined = TokenizerOptions::default();
+    assert_eq!(words("don't stop, O'Brien", &joined), ["don't", "stop", "O'Brien"]);
+
+    let split = TokenizerOptions { join_apostrophes: false, ..Default::default() };
+    assert_eq!(words("don't stop, O'Brien", &split), ["don", "t", "stop", "O", "Brien"]);
+}
+
+#[test]
+fn hyphenated_words_are_configurable() {
+    let split = TokenizerOptions::default();
+    assert_eq!(words("a well-known fact", &split), ["a", "well", "known", "fact"]);
+
+    let joined = TokenizerOptions { join_hyphens: true, ..Default::default() };
+    assert_eq!(words("a well-known fact", &joined), ["a", "well-known", "fact"]);
+    assert_eq!(words("pre- and post-war", &joined), ["pre", "and", "post-war"]);
+}
+
+#[test]
+fn numbers_are_configurable() {
+    let guessable = TokenizerOptions::default();
+    assert_eq!(words("pi is 3.14, not 3rd", &guessable), ["pi", "is", "3.14", "not", "3rd"]);
+
+    let revealed = TokenizerOptions { numbers: NumberRule::Revealed, ..Default::default() };
+    assert_eq!(words("pi is 3.14, not 3rd", &revealed), ["pi", "is", "not", "3rd"]);
+    assert_eq!(concat(&chop_into_tokens("in 1,000 years", &revealed)), "in 1,000 years");
+}
+
+#[test]
+fn scripts_without_spaces_are_split_into_words() {
+    let options = TokenizerOptions::default();
+
+    // Without a dictionary every Han character is a word of its own.
+    assert_eq!(words("日本の首都", &options), ["日", "本", "の", "首", "都"]);
+
+    assert_eq!(words("תל אביב", &options), ["תל", "אביב"]);
+}
+
+#[cfg(feature = "dictionary-segmentation")]
+#[test]
+fn dictionary_splits_scripts_without_spaces() {
+    let options = TokenizerOptions {
+        dictionary_segmentation: true,
+        ..TokenizerOptions::default()
+    };
+
+    assert_eq!(words("ภาษาไทยง่าย", &options), ["ภาษา", "ไทย", "ง่าย"]);
+    assert_eq!(words("ພາສາລາວ", &options), ["ພາສາ", "ລາວ"]);
+    assert_eq!(words("我们中出了一个叛徒", &options), ["我们", "中", "出", "了", "一个", "叛徒"]);
+}
@@ -0,0 +1,245 @@
+#![feature(iter_intersperse)]
+#![allow(dead_code)]
+
+#[path = "../src/config.rs"]
+mod config;
+#[path = "../src/http_client.rs"]
+mod http_client;
+#[path = "../src/languages.rs"]
+mod languages;
+#[path = "../src/wiki_endpoint.rs"]
+mod wiki_endpoint;
+#[path = "../src/wikipedia_api.rs"]
+mod wikipedia_api;
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::thread::JoinHandle;
+
+use reqwest::Url;
+
+use crate::wiki_endpoint::WikiEndpoint;
+use crate::wikipedia_api::ArticleError;
+
+struct Request {
+    path: String,
+    // Header names are lowercase
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+// Answers one request per body, in order, and returns what was requested.
+fn serve(bodies: Vec<&'static str>) -> (u16, JoinHandle<Vec<Request>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+
+        for body in bodies {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let path = request_line.split_whitespace().nth(1).unwrap_or("").to_string();
+
+            let mut headers = Vec::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+
+                if let Some((name, value)) = line.split_once(':') {
+                    headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+                }
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            ).unwrap();
+
+            requests.push(Request { path: path, headers: headers });
+        }
+
+        requests
+    });
+
+    (port, handle)
+}
+
+fn serve_once(body: &'static str) -> (u16, JoinHandle<Request>) {
+    let (port, server) = serve(vec![body]);
+    let handle = std::thread::spawn(move || server.join().unwrap().remove(0));
+
+    (port, handle)
+}
+
+fn mock_endpoint(port: u16) -> WikiEndpoint {
+    WikiEndpoint::new("Mock", &format!("http://127.0.0.1:{}/w/api.php", port))
+}
+
+// The decoded value of a query parameter of the request
+fn query_param(request: &Request, name: &str) -> Option<String> {
+    let url = Url::parse(&format!("http://localhost{}", request.path)).unwrap();
+    url.query_pairs()
+        .find(|(n, _)| n == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+const SEARCH_RESPONSE: &str = r#"{"query":{"search":[{"title":"Berlin"},{"title":"Berlin Wall"}]}}"#;
+
+#[test]
+fn language_is_substituted_into_the_api_url() {
+    let (port, server) = serve_once(SEARCH_RESPONSE);
+    let endpoint = WikiEndpoint::new("Mock", &format!("http://127.0.0.1:{}/{{language}}/w/api.php", port));
+
+    let titles = wikipedia_api::search_titles(&endpoint, "de", "Berlin", 5).unwrap();
+    assert_eq!(titles, ["Berlin", "Berlin Wall"]);
+
+    let request = server.join().unwrap();
+    assert!(request.path.starts_with("/de/w/api.php?"), "{}", request.path);
+    assert!(request.path.contains("srsearch=Berlin"), "{}", request.path);
+}
+
+#[test]
+fn default_user_agent_is_sent() {
+    let (port, server) = serve_once(SEARCH_RESPONSE);
+    let endpoint = WikiEndpoint::new("Mock", &format!("http://127.0.0.1:{}/w/api.php", port));
+
+    wikipedia_api::search_titles(&endpoint, "en", "Berlin", 5).unwrap();
+
+    let request = server.join().unwrap();
+    assert_eq!(request.header("user-agent"), Some(http_client::USER_AGENT));
+}
+
+#[test]
+fn custom_user_agent_and_headers_are_sent() {
+    let (port, server) = serve_once(SEARCH_RESPONSE);
+    let mut endpoint = WikiEndpoint::new("Mock", &format!("http://127.0.0.1:{}/w/api.php", port));
+    endpoint.user_agent = Some(String::from("CompanyWikiBot/1.0"));
+    endpoint.headers = vec![
+        (String::from("Authorization"), String::from("Bearer secret")),
+        (String::from(" X-Team "), String::from(" puzzles ")),
+    ];
+
+    wikipedia_api::search_titles(&endpoint, "en", "Berlin", 5).unwrap();
+
+    let request = server.join().unwrap();
+    assert_eq!(request.header("user-agent"), Some("CompanyWikiBot/1.0"));
+    assert_eq!(request.header("authorization"), Some("Bearer secret"));
+    assert_eq!(request.header("x-team"), Some("puzzles"));
+}
+
+const ARTICLE_RESPONSE: &str = r#"{"parse":{"title":"Berlin","wikitext":"'''Berlin''' is the capital of Germany.","properties":{},"links":[]}}"#;
+
+#[test]
+fn articles_are_downloaded() {
+    let (port, server) = serve_once(ARTICLE_RESPONSE);
+
+    let (title, wikitext) = wikipedia_api::download_article(&mock_endpoint(port), "en", "Berlin").unwrap();
+    assert_eq!(title, "Berlin");
+    assert_eq!(wikitext, "'''Berlin''' is the capital of Germany.");
+
+    let request = server.join().unwrap();
+    assert_eq!(query_param(&request, "action").as_deref(), Some("parse"));
+    assert_eq!(query_param(&request, "page").as_deref(), Some("Berlin"));
+}
+
+#[test]
+fn redirects_are_followed() {
+    // The wiki resolves the redirect and answers with the target page.
+    let (port, server) = serve_once(ARTICLE_RESPONSE);
+
+    let (title, _) = wikipedia_api::download_article(&mock_endpoint(port), "en", "Berlin, Germany").unwrap();
+    assert_eq!(title, "Berlin");
+
+    let request = server.join().unwrap();
+    assert_eq!(query_param(&request, "redirects").as_deref(), Some("1"));
+    assert_eq!(query_param(&request, "page").as_deref(), Some("Berlin, Germany"));
+}
+
+#[test]
+fn titles_are_encoded() {
+    for title in ["AT&T Building", "Zürich", "東京都", "C++ (programming language)"] {
+        let (port, server) = serve_once(ARTICLE_RESPONSE);
+
+        wikipedia_api::download_article(&mock_endpoint(port), "en", title).unwrap();
+
+        let request = server.join().unwrap();
+        assert!(!request.path.contains(' '), "{}", request.path);
+        assert!(request.path.is_ascii(), "{}", request.path);
+        assert_eq!(query_param(&request, "page").as_deref(), Some(title));
+        assert_eq!(query_param(&request, "format").as_deref(), Some("json"));
+    }
+}
+
+#[test]
+fn missing_articles_come_with_suggestions() {
+    let (port, server) = serve(vec![
+        r#"{"error":{"code":"missingtitle","info":"The page you specified doesn't exist."}}"#,
+        SEARCH_RESPONSE,
+    ]);
+
+    let error = wikipedia_api::download_article(&mock_endpoint(port), "en", "Berlinn").unwrap_err();
+    match error.downcast_ref::<ArticleError>() {
+        Some(ArticleError::Missing { title, suggestions }) => {
+            assert_eq!(title, "Berlinn");
+            assert_eq!(suggestions, &["Berlin", "Berlin Wall"]);
+        }
+        _ => panic!("unexpected error: {}", error),
+    }
+
+    let requests = server.join().unwrap();
+    assert_eq!(query_param(&requests[1], "list").as_deref(), Some("search"));
+    assert_eq!(query_param(&requests[1], "srsearch").as_deref(), Some("Berlinn"));
+}
+
+#[test]
+fn other_api_errors_are_reported() {
+    let (port, server) = serve_once(r#"{"error":{"code":"invalidtitle","info":"Bad title."}}"#);
+
+    let error = wikipedia_api::download_article(&mock_endpoint(port), "en", "<").unwrap_err();
+    match error.downcast_ref::<ArticleError>() {
+        Some(ArticleError::Api { code, .. }) => assert_eq!(code, "invalidtitle"),
+        _ => panic!("unexpected error: {}", error),
+    }
+
+    server.join().unwrap();
+}
+
+#[test]
+fn disambiguation_pages_list_their_options() {
+    let (port, server) = serve_once(r#"{"parse":{"title":"Mercury","wikitext":"'''Mercury''' may refer to:","properties":{"disambiguation":""},"links":[
+        {"ns":0,"exists":true,"title":"Mercury (planet)"},
+        {"ns":0,"exists":true,"title":"Mercury (element)"},
+        {"ns":0,"exists":false,"title":"Mercury (missing)"},
+        {"ns":14,"exists":true,"title":"Category:Disambiguation pages"}
+    ]}}"#);
+
+    let error = wikipedia_api::download_article(&mock_endpoint(port), "en", "Mercury").unwrap_err();
+    match error.downcast_ref::<ArticleError>() {
+        Some(ArticleError::Disambiguation { title, options }) => {
+            assert_eq!(title, "Mercury");
+            assert_eq!(options, &["Mercury (planet)", "Mercury (element)"]);
+        }
+        _ => panic!("unexpected error: {}", error),
+    }
+
+    server.join().unwrap();
+}
@@ -0,0 +1,97 @@
+#![allow(dead_code)]
+
+mod common;
+
+#[path = "../src/word_vectors.rs"]
+mod word_vectors;
+
+use std::path::{Path, PathBuf};
+
+use crate::common::TempDir;
+use crate::word_vectors::{WordVectors, MAX_WORDS};
+
+fn fixture() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("data").join("word_vectors.vec")
+}
+
+#[test]
+fn vectors_are_loaded() {
+    let vectors = WordVectors::load(&fixture()).unwrap();
+
+    // "King" is a second spelling of "king"
+    assert_eq!(vectors.len(), 5);
+    assert!(vectors.contains("queen"));
+    assert!(vectors.contains("Queen"));
+    assert!(!vectors.contains("banana"));
+}
+
+#[test]
+fn the_first_spelling_of_a_word_is_kept() {
+    let vectors = WordVectors::load(&fixture()).unwrap();
+
+    // "King" points elsewhere, but comes after "king"
+    assert!(vectors.similarity("King", "queen").unwrap() > 0.9);
+}
+
+#[test]
+fn similar_words_are_closer() {
+    let vectors = WordVectors::load(&fixture()).unwrap();
+
+    let queen = vectors.similarity("king", "queen").unwrap();
+    let man = vectors.similarity("king", "man").unwrap();
+    let apple = vectors.similarity("king", "apple").unwrap();
+    assert!(queen > man, "{} {}", queen, man);
+    assert!(man > apple, "{} {}", man, apple);
+
+    // Vectors are normalized
+    assert!((vectors.similarity("pear", "pear").unwrap() - 1.0).abs() < 1e-5);
+    assert_eq!(vectors.similarity("king", "banana"), None);
+}
+
+#[test]
+fn closest_similarity_uses_the_best_target() {
+    let vectors = WordVectors::load(&fixture()).unwrap();
+    let targets = vec![String::from("apple"), String::from("queen"), String::from("banana")];
+
+    let closest = vectors.closest_similarity("king", &targets).unwrap();
+    assert_eq!(closest, vectors.similarity("king", "queen").unwrap());
+
+    assert_eq!(vectors.closest_similarity("banana", &targets), None);
+    assert_eq!(vectors.closest_similarity("king", &[]), None);
+}
+
+#[test]
+fn files_need_a_header() {
+    let dir = TempDir::new();
+
+    let error = WordVectors::load(&dir.write("empty.vec", "")).err().unwrap();
+    assert!(error.to_string().contains("is empty"), "{}", error);
+
+    let error = WordVectors::load(&dir.write("headless.vec", "king 0.1 0.2\n")).err().unwrap();
+    assert!(error.to_string().contains("header"), "{}", error);
+}
+
+#[test]
+fn rows_with_the_wrong_dimension_are_rejected() {
+    let dir = TempDir::new();
+    let path = dir.write("short.vec", "2 3\nking 0.1 0.2 0.3\nqueen 0.1 0.2\n");
+
+    let error = WordVectors::load(&path).err().unwrap();
+    assert!(error.to_string().contains("\"queen\" has 2 instead of 3"), "{}", error);
+}
+
+#[test]
+fn only_the_most_frequent_words_are_loaded() {
+    let dir = TempDir::new();
+
+    let mut content = format!("{} 1\n", MAX_WORDS + 10);
+    for i in 0..MAX_WORDS + 10 {
+        content.push_str(&format!("w{} 1\n", i));
+    }
+    let path = dir.write("large.vec", content);
+
+    let vectors = WordVectors::load(&path).unwrap();
+    assert_eq!(vectors.len(), MAX_WORDS);
+    assert!(vectors.contains(&format!("w{}", MAX_WORDS - 1)));
+    assert!(!vectors.contains(&format!("w{}", MAX_WORDS)));
+}